@@ -18,6 +18,7 @@ async fn main() -> Result<(), Error> {
 
     let (mut client, mut subscription) = drb.connect().await?;
 
+    #[allow(clippy::let_underscore_future)]
     let _ = client
         .call(AuthRequest::credential_auth(&key, &secret))
         .await?;
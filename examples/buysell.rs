@@ -18,6 +18,7 @@ async fn main() {
     let (mut client, _) = drb.connect().await?;
 
     let req = AuthRequest::credential_auth(&key, &secret);
+    #[allow(clippy::let_underscore_future)]
     let _ = client.call(req).await?;
 
     let req = BuyRequest::market("BTC-PERPETUAL", 10f64);
@@ -19,15 +19,14 @@ async fn main() -> Result<(), Error> {
 
     while let Some(Ok(sub)) = subscription.next().await {
         if sub.is_heartbeat() {
-            match sub.params {
-                SubscriptionParams::Heartbeat { r#type: ty } => match ty {
+            if let SubscriptionParams::Heartbeat { r#type: ty } = sub.params {
+                match ty {
                     HeartbeatType::TestRequest => {
                         println!("Test Requested");
                         client.call(TestRequest::default()).await?;
                     }
                     _ => println!("Heartbeat"),
-                },
-                _ => {}
+                }
             }
         }
     }
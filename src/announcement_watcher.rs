@@ -0,0 +1,150 @@
+use crate::models::subscription::AnnouncementsData;
+use crate::models::{
+    Announcement, GetAnnouncementsRequest, GetNewAnnouncementsRequest, SetAnnouncementAsReadRequest,
+};
+use crate::DeribitAPIClient;
+use failure::Error;
+use fehler::throws;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Deribit returns at most 50 announcements per call, and 5 by default
+const HISTORY_PAGE_SIZE: u64 = 50;
+const DEFAULT_HISTORY_PAGE_SIZE: u64 = 5;
+
+/// The exchange announcements, merging the history fetched through the API with the
+/// `announcements` notifications and keeping track of the unread ones.
+///
+/// Announcements are deduplicated by id, so the history can be reloaded at any time,
+/// e.g. after a reconnection, without surfacing the same announcement twice.
+#[derive(Debug, Clone, Default)]
+pub struct AnnouncementWatcher {
+    announcements: BTreeMap<u64, Announcement>,
+    unread: BTreeSet<u64>,
+}
+
+impl AnnouncementWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetches the whole announcements history and the unread announcements, returning
+    /// the ones not seen before.
+    #[throws(Error)]
+    pub async fn load(&mut self, client: &mut DeribitAPIClient) -> Vec<Announcement> {
+        let mut added = vec![];
+        let mut req = GetAnnouncementsRequest {
+            start_timestamp: None,
+            count: Some(HISTORY_PAGE_SIZE),
+        };
+        loop {
+            let page = client.call(req.clone()).await?.await?;
+            let (new, older) = self.on_history(&req, page);
+            added.extend(new);
+            match older {
+                Some(older) => req = older,
+                None => break,
+            }
+        }
+
+        let unread = client.call(GetNewAnnouncementsRequest).await?.await?;
+        added.extend(self.on_unread(unread));
+        added
+    }
+
+    /// Applies a page of the announcements history fetched with `req`, returning the
+    /// announcements not seen before and the request for the page before it, if any.
+    ///
+    /// Paging stops at a page which is not full or brings nothing new, as the older
+    /// announcements were then loaded already.
+    pub fn on_history(
+        &mut self,
+        req: &GetAnnouncementsRequest,
+        page: Vec<Announcement>,
+    ) -> (Vec<Announcement>, Option<GetAnnouncementsRequest>) {
+        let full = page.len() as u64 >= req.count.unwrap_or(DEFAULT_HISTORY_PAGE_SIZE);
+        let oldest = page.iter().map(|a| a.date).min();
+        let mut added = vec![];
+        for announcement in page {
+            added.extend(self.insert(announcement, false));
+        }
+
+        // The oldest date is kept, announcements sharing it may span both pages
+        let older = match oldest {
+            Some(date) if full && !added.is_empty() => Some(GetAnnouncementsRequest::before(
+                date,
+                req.count.unwrap_or(DEFAULT_HISTORY_PAGE_SIZE),
+            )),
+            _ => None,
+        };
+        (added, older)
+    }
+
+    /// Applies the result of `get_new_announcements`, which replaces the unread
+    /// announcements, returning the ones not seen before.
+    pub fn on_unread(&mut self, unread: Vec<Announcement>) -> Vec<Announcement> {
+        self.unread = unread.iter().map(|a| a.id).collect();
+        unread
+            .into_iter()
+            .filter_map(|announcement| self.insert(announcement, true))
+            .collect()
+    }
+
+    /// Applies an `announcements` notification, returning the announcement if it is new.
+    pub fn on_notification(&mut self, data: &AnnouncementsData) -> Option<Announcement> {
+        if data.action == "delete" {
+            self.announcements.remove(&data.id);
+            self.unread.remove(&data.id);
+            return None;
+        }
+        self.insert(data.into(), true)
+    }
+
+    fn insert(&mut self, announcement: Announcement, unread: bool) -> Option<Announcement> {
+        if self.announcements.contains_key(&announcement.id) {
+            return None;
+        }
+        if unread {
+            self.unread.insert(announcement.id);
+        }
+        self.announcements
+            .insert(announcement.id, announcement.clone());
+        Some(announcement)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Announcement> {
+        self.announcements.get(&id)
+    }
+
+    /// Every announcement, the most recent first.
+    pub fn announcements(&self) -> impl Iterator<Item = &Announcement> {
+        let mut announcements: Vec<_> = self.announcements.values().collect();
+        announcements.sort_by_key(|a| std::cmp::Reverse((a.date, a.id)));
+        announcements.into_iter()
+    }
+
+    /// The unread announcements, the most recent first.
+    pub fn unread(&self) -> impl Iterator<Item = &Announcement> {
+        self.announcements()
+            .filter(move |a| self.unread.contains(&a.id))
+    }
+
+    pub fn is_unread(&self, id: u64) -> bool {
+        self.unread.contains(&id)
+    }
+
+    #[throws(Error)]
+    pub async fn mark_read(&mut self, client: &mut DeribitAPIClient, id: u64) {
+        client
+            .call(SetAnnouncementAsReadRequest::new(id))
+            .await?
+            .await?;
+        self.unread.remove(&id);
+    }
+
+    #[throws(Error)]
+    pub async fn mark_all_read(&mut self, client: &mut DeribitAPIClient) {
+        for id in self.unread.clone() {
+            self.mark_read(client, id).await?;
+        }
+    }
+}
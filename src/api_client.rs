@@ -1,5 +1,5 @@
 use crate::errors::{DeribitError, Result};
-use crate::models::{JSONRPCRequest, JSONRPCResponse, Request};
+use crate::models::{ChunkedRequest, JSONRPCRequest, JSONRPCResponse, PaginatedRequest, Request};
 use crate::WSStream;
 use failure::Error;
 use fehler::throws;
@@ -7,7 +7,7 @@ use futures::{
     channel::{mpsc, oneshot},
     stream::SplitSink,
     task::{Context, Poll},
-    {Future, SinkExt},
+    {stream, stream::BoxStream, Future, SinkExt, StreamExt, TryStreamExt},
 };
 use log::{error, trace};
 use pin_project::pin_project;
@@ -33,9 +33,9 @@ impl DeribitAPIClient {
         timeout: Duration,
     ) -> DeribitAPIClient {
         DeribitAPIClient {
-            wstx: wstx,
-            waiter_tx: waiter_tx,
-            timeout: timeout,
+            wstx,
+            waiter_tx,
+            timeout,
             id: 0,
         }
     }
@@ -68,6 +68,65 @@ impl DeribitAPIClient {
         let resp: DeribitAPICallRawResult<R::Response> = self.call_raw(request).await?;
         DeribitAPICallResult::new(resp)
     }
+
+    /// Walks all the pages of a paginated request, yielding the items one by one.
+    /// The next page is only requested once the items of the current one are consumed.
+    pub fn call_paginated<'a, R>(&'a mut self, request: R) -> BoxStream<'a, Result<R::Item>>
+    where
+        R: PaginatedRequest + Serialize + Send + 'a,
+        R::Response: DeserializeOwned + Send,
+        R::Item: Send + 'a,
+    {
+        stream::try_unfold((self, Some(request)), |(client, request)| async move {
+            let request = match request {
+                Some(request) => request,
+                None => return Ok::<_, Error>(None),
+            };
+            let page = client.call(request.clone()).await?.await?;
+            let (items, next) = request.paginate(page)?;
+            Ok(Some((
+                stream::iter(items.into_iter().map(Ok)),
+                (client, next),
+            )))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Splits the range of the request into windows the API accepts and fetches them one
+    /// after the other, yielding the items of the whole range in order.
+    pub fn call_chunked<'a, R>(&'a mut self, request: R) -> BoxStream<'a, Result<R::Item>>
+    where
+        R: ChunkedRequest + Serialize + Send + 'a,
+        R::Response: DeserializeOwned + Send,
+        R::Item: Send + 'a,
+    {
+        let (start, end) = request.range();
+        let window = request.max_window().num_milliseconds().max(1) as u64;
+        stream::try_unfold((self, Some(start), None), move |(client, start, last)| {
+            let request = request.clone();
+            async move {
+                let start = match start {
+                    Some(start) if start <= end => start,
+                    _ => return Ok::<_, Error>(None),
+                };
+                // Both ends are inclusive, the next window starts right after this one
+                let window_end = end.min(start.saturating_add(window - 1));
+                let resp = client
+                    .call(request.with_range(start, window_end))
+                    .await?
+                    .await?;
+                let next = window_end.checked_add(1).filter(|_| window_end < end);
+                let (items, last) = R::dedup(R::items(resp)?, last);
+                Ok(Some((
+                    stream::iter(items.into_iter().map(Ok)),
+                    (client, next, last),
+                )))
+            }
+        })
+        .try_flatten()
+        .boxed()
+    }
 }
 
 #[pin_project]
@@ -97,7 +156,7 @@ where
             Poll::Ready(Ok(ret)) => Poll::Ready(match ret {
                 Ok(resp) => {
                     let result: StdResult<JSONRPCResponse<R>, _> = from_str(&resp);
-                    if let Err(_) = result.as_ref() {
+                    if result.as_ref().is_err() {
                         error!("[API Client] Cannot deserialize RPC response: {}", resp);
                     }
                     result.map_err(Into::into)
@@ -119,7 +178,7 @@ pub struct DeribitAPICallResult<R> {
 
 impl<R> DeribitAPICallResult<R> {
     pub(crate) fn new(inner: DeribitAPICallRawResult<R>) -> Self {
-        DeribitAPICallResult { inner: inner }
+        DeribitAPICallResult { inner }
     }
 }
 
@@ -0,0 +1,66 @@
+//! The two-party flow of a block trade: one party verifies the proposal and hands the
+//! signature over, the counterparty executes it.
+
+use crate::models::{BlockTrade, BlockTradeProposal, BlockTradeRole, SignedBlockTrade};
+use crate::DeribitAPIClient;
+use failure::Error;
+use fehler::{throw, throws};
+use thiserror::Error as ThisError;
+
+/// A failed execution whose signature could not be invalidated either, so that it might
+/// still be executed later on.
+#[derive(Debug, ThisError)]
+#[error("{execute}, and invalidating the signature failed: {invalidate}")]
+pub struct ExecutionFailed {
+    pub execute: Error,
+    pub invalidate: Error,
+}
+
+/// Verifies a proposal as `role`, returning the signed trade to send to the counterparty.
+#[throws(Error)]
+pub async fn sign(
+    client: &mut DeribitAPIClient,
+    proposal: BlockTradeProposal,
+    role: BlockTradeRole,
+) -> SignedBlockTrade {
+    let verified = client.call(proposal.verify(role)).await?.await?;
+    proposal.signed(role, verified.signature)
+}
+
+/// Executes a trade signed by the counterparty, taking the opposite role.
+#[throws(Error)]
+pub async fn execute(client: &mut DeribitAPIClient, signed: &SignedBlockTrade) -> BlockTrade {
+    client.call(signed.execute()).await?.await?
+}
+
+/// Runs the whole flow between two accounts held by the same process, `signer` verifying
+/// as `role` and `executor` executing as its counterparty.
+///
+/// When the execution fails the signature is invalidated, so that it cannot be executed
+/// later on. The error is the one of the execution, wrapped in `ExecutionFailed` if the
+/// invalidation failed too.
+#[throws(Error)]
+pub async fn execute_between(
+    signer: &mut DeribitAPIClient,
+    executor: &mut DeribitAPIClient,
+    proposal: BlockTradeProposal,
+    role: BlockTradeRole,
+) -> BlockTrade {
+    let signed = sign(signer, proposal, role).await?;
+    match execute(executor, &signed).await {
+        Ok(trade) => trade,
+        Err(execute) => {
+            let invalidated = match signer.call(signed.invalidate()).await {
+                Ok(resp) => resp.await.map(drop),
+                Err(e) => Err(e),
+            };
+            match invalidated {
+                Ok(()) => throw!(execute),
+                Err(invalidate) => throw!(ExecutionFailed {
+                    execute,
+                    invalidate
+                }),
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use crate::models::subscription::{ChartTradesData, TradesData};
+use crate::models::{Candle, InstrumentName, Resolution};
+use chrono::Duration;
+use std::collections::{BTreeMap, BTreeSet};
+
+// How many trade sequence numbers below the highest one are remembered to drop duplicates
+const SEQ_WINDOW: u64 = 100_000;
+
+#[derive(Debug, Clone)]
+struct Bar {
+    candle: Candle,
+    // Sequence numbers of the trades that set the open and the close, None for seeded bars
+    first_seq: Option<u64>,
+    last_seq: Option<u64>,
+    // Set by `chart.trades`, which then covers every trade of the period
+    from_chart: bool,
+}
+
+impl Bar {
+    fn seeded(candle: Candle) -> Self {
+        Self {
+            candle,
+            first_seq: None,
+            last_seq: None,
+            from_chart: false,
+        }
+    }
+}
+
+/// Builds OHLCV candles of any resolution from trades.
+///
+/// Seed it with the candles of `GetTradingviewChartDataRequest` along with the `trade_seq`
+/// of the last trade they include, then feed it the `trades.*` notifications. Trades
+/// already covered by the seed or already applied are skipped, and late trades update the
+/// candle they belong to, the open and close following `trade_seq` rather than arrival order.
+///
+/// Once a period received a `chart.trades` candle, that channel is authoritative for it:
+/// its candles replace the aggregated one and the trades of the period are skipped.
+///
+/// As on the `get_tradingview_chart_data` candles, `volume` is in base currency and
+/// `cost` in quote currency: trade amounts of inverse futures are in USD.
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    resolution: u64,
+    inverse: bool,
+    bars: BTreeMap<u64, Bar>,
+    seeded_seq: Option<u64>,
+    seen: BTreeSet<u64>,
+}
+
+impl CandleAggregator {
+    pub fn new(instrument_name: &str, resolution: Duration) -> Self {
+        let inverse = match instrument_name.parse() {
+            Ok(InstrumentName::Perpetual { underlying })
+            | Ok(InstrumentName::Future { underlying, .. }) => !underlying.contains('_'),
+            _ => false,
+        };
+        Self {
+            resolution: resolution.num_milliseconds().max(1) as u64,
+            inverse,
+            bars: BTreeMap::new(),
+            seeded_seq: None,
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// Merges candles of this resolution or of a finer one dividing it.
+    /// `last_trade_seq` is the sequence number of the last trade they include.
+    ///
+    /// The candles replace the ones of the periods they overlap, so seeding the same range
+    /// twice does not count it twice.
+    pub fn seed(&mut self, candles: &[Candle], last_trade_seq: u64) {
+        let mut candles = candles.to_vec();
+        candles.sort_by_key(|c| c.timestamp);
+        let mut seeded = BTreeMap::<u64, Candle>::new();
+        for candle in candles {
+            let start = self.bucket(candle.timestamp);
+            seeded
+                .entry(start)
+                .and_modify(|c| {
+                    c.high = c.high.max(candle.high);
+                    c.low = c.low.min(candle.low);
+                    c.close = candle.close;
+                    c.volume += candle.volume;
+                    c.cost += candle.cost;
+                })
+                .or_insert(Candle {
+                    timestamp: start,
+                    ..candle
+                });
+        }
+        for (start, candle) in seeded {
+            self.bars.insert(start, Bar::seeded(candle));
+        }
+        self.seeded_seq = Some(
+            self.seeded_seq
+                .map_or(last_trade_seq, |s| s.max(last_trade_seq)),
+        );
+        self.seen.retain(|&seq| seq > last_trade_seq);
+    }
+
+    /// Applies a trade, returning the updated candle or `None` if the trade was skipped.
+    pub fn on_trade(&mut self, trade: &TradesData) -> Option<Candle> {
+        let seq = trade.trade_seq;
+        let start = self.bucket(trade.timestamp);
+        if self.bars.get(&start).map_or(false, |bar| bar.from_chart) {
+            return None;
+        }
+        if self.seeded_seq.map_or(false, |s| seq <= s) || !self.seen.insert(seq) {
+            return None;
+        }
+        let max = *self.seen.iter().next_back().unwrap();
+        while let Some(&first) = self.seen.iter().next() {
+            if first + SEQ_WINDOW >= max {
+                break;
+            }
+            self.seen.remove(&first);
+        }
+
+        let (volume, cost) = if self.inverse {
+            (trade.amount / trade.price, trade.amount)
+        } else {
+            (trade.amount, trade.amount * trade.price)
+        };
+        let bar = self.bars.entry(start).or_insert(Bar {
+            candle: Candle {
+                timestamp: start,
+                open: trade.price,
+                high: trade.price,
+                low: trade.price,
+                close: trade.price,
+                volume: 0.,
+                cost: 0.,
+            },
+            first_seq: Some(seq),
+            last_seq: Some(seq),
+            from_chart: false,
+        });
+        let c = &mut bar.candle;
+        c.high = c.high.max(trade.price);
+        c.low = c.low.min(trade.price);
+        c.volume += volume;
+        c.cost += cost;
+        if bar.first_seq.map_or(false, |s| seq < s) {
+            c.open = trade.price;
+            bar.first_seq = Some(seq);
+        }
+        if bar.last_seq.map_or(true, |s| seq > s) {
+            c.close = trade.price;
+            bar.last_seq = Some(seq);
+        }
+        Some(*c)
+    }
+
+    /// Replaces a candle with the one of the `chart.trades` channel, returning whether it
+    /// was taken: candles of another resolution than the aggregator's are ignored.
+    pub fn on_chart(&mut self, resolution: Resolution, data: &ChartTradesData) -> bool {
+        if resolution.duration().num_milliseconds() as u64 != self.resolution {
+            return false;
+        }
+        let candle = Candle::from(data);
+        let start = self.bucket(candle.timestamp);
+        self.bars.insert(
+            start,
+            Bar {
+                candle: Candle {
+                    timestamp: start,
+                    ..candle
+                },
+                first_seq: None,
+                last_seq: None,
+                from_chart: true,
+            },
+        );
+        true
+    }
+
+    pub fn candles(&self) -> impl Iterator<Item = &Candle> {
+        self.bars.values().map(|bar| &bar.candle)
+    }
+
+    pub fn last(&self) -> Option<&Candle> {
+        self.bars.values().next_back().map(|bar| &bar.candle)
+    }
+
+    /// The candles whose period ended before `timestamp`.
+    pub fn closed(&self, timestamp: u64) -> impl Iterator<Item = &Candle> {
+        let current = self.bucket(timestamp);
+        self.bars.range(..current).map(|(_, bar)| &bar.candle)
+    }
+
+    /// Drops the oldest candles, keeping at most `count`.
+    pub fn truncate(&mut self, count: usize) {
+        while self.bars.len() > count {
+            let first = *self.bars.keys().next().unwrap();
+            self.bars.remove(&first);
+        }
+    }
+
+    fn bucket(&self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.resolution
+    }
+}
@@ -0,0 +1,58 @@
+use crate::models::{Currency, CurrencyInfo, GetCurrenciesRequest};
+use crate::DeribitAPIClient;
+use failure::Error;
+use fehler::throws;
+use std::collections::HashMap;
+
+/// The metadata of the currencies listed on Deribit, as returned by `GetCurrenciesRequest`.
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyRegistry {
+    currencies: HashMap<Currency, CurrencyInfo>,
+}
+
+impl CurrencyRegistry {
+    pub fn new(currencies: Vec<CurrencyInfo>) -> Self {
+        Self {
+            currencies: currencies
+                .into_iter()
+                .map(|info| (info.currency.clone(), info))
+                .collect(),
+        }
+    }
+
+    #[throws(Error)]
+    pub async fn load(client: &mut DeribitAPIClient) -> Self {
+        Self::new(client.call(GetCurrenciesRequest).await?.await?)
+    }
+
+    pub fn get(&self, currency: &Currency) -> Option<&CurrencyInfo> {
+        self.currencies.get(currency)
+    }
+
+    pub fn currencies(&self) -> impl Iterator<Item = &Currency> {
+        self.currencies.keys()
+    }
+
+    pub fn fee_precision(&self, currency: &Currency) -> Option<u32> {
+        self.get(currency).map(|info| info.fee_precision)
+    }
+
+    pub fn min_confirmations(&self, currency: &Currency) -> Option<u32> {
+        self.get(currency).map(|info| info.min_confirmations)
+    }
+
+    pub fn withdrawal_fee(&self, currency: &Currency) -> Option<f64> {
+        self.get(currency).map(|info| info.withdrawal_fee)
+    }
+
+    /// Rounds a fee to the precision of the currency, unknown currencies are left as is.
+    pub fn round_fee(&self, currency: &Currency, fee: f64) -> f64 {
+        match self.fee_precision(currency) {
+            Some(precision) => {
+                let scale = 10f64.powi(precision as i32);
+                (fee * scale).round() / scale
+            }
+            None => fee,
+        }
+    }
+}
@@ -18,6 +18,24 @@ pub enum DeribitError {
     UnknownCurrency(String),
     #[error("Unknown asset kind {0}")]
     UnknownAssetKind(String),
+    #[error("Invalid API key scope {0}")]
+    InvalidApiKeyScope(String),
+    #[error("Invalid instrument name {0}")]
+    InvalidInstrumentName(String),
+    #[error("Order book of {instrument_name} out of sync, expected change {expected} got {prev_change_id:?}")]
+    OrderBookOutOfSync {
+        instrument_name: String,
+        expected: i64,
+        prev_change_id: Option<i64>,
+    },
+    #[error("Invalid leg on {0}, it must buy or sell a positive ratio")]
+    InvalidLeg(String),
+    #[error("Chart data columns have different lengths")]
+    InvalidChartData,
+    #[error("Order on {0} has no price")]
+    MissingPrice(String),
+    #[error("More than {count} trades share the timestamp {timestamp}, they cannot be paginated")]
+    PaginationStalled { timestamp: u64, count: u64 },
     #[error("Websocket disconnected")]
     WebsocketDisconnected,
     #[error("Request timed out")]
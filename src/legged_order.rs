@@ -0,0 +1,214 @@
+use crate::errors::DeribitError;
+use crate::models::subscription::UserOrdersData;
+use crate::models::{
+    BuyRequest, Currency, Direction, GetOrderStateRequest, Order, OrderState, SellRequest,
+};
+use crate::order_group::OrderGroup;
+use crate::DeribitAPIClient;
+use failure::Error;
+use fehler::{throw, throws};
+use std::ops::DerefMut;
+
+/// One leg of a structure, `ratio` is the leg amount per unit of the structure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    pub instrument_name: String,
+    pub direction: Direction,
+    pub ratio: f64,
+    pub price: f64,
+}
+
+impl Leg {
+    pub fn buy(instrument_name: &str, ratio: f64, price: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            direction: Direction::Buy,
+            ratio,
+            price,
+        }
+    }
+
+    pub fn sell(instrument_name: &str, ratio: f64, price: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            direction: Direction::Sell,
+            ratio,
+            price,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LegStatus {
+    pub leg: Leg,
+    pub order_id: Option<String>,
+    pub order_state: Option<OrderState>,
+    pub filled_amount: f64,
+}
+
+impl LegStatus {
+    fn is_done(&self) -> bool {
+        match self.order_state {
+            Some(OrderState::Open) | Some(OrderState::Untriggered) | None => false,
+            Some(_) => true,
+        }
+    }
+}
+
+/// Places the legs of a structure that has no listed combo as separate limit orders
+/// sharing one label, and tracks them as a single logical order.
+#[derive(Debug, Clone)]
+pub struct LeggedOrder {
+    group: OrderGroup,
+    amount: f64,
+    legs: Vec<LegStatus>,
+}
+
+impl LeggedOrder {
+    /// `amount` is the number of structure units, every leg trades `amount * ratio`.
+    ///
+    /// Legs must buy or sell a positive, finite ratio.
+    #[throws(DeribitError)]
+    pub fn new<S: Into<String>>(label: S, amount: f64, legs: Vec<Leg>) -> Self {
+        for leg in &legs {
+            if leg.direction == Direction::Zero || !(leg.ratio.is_finite() && leg.ratio > 0.) {
+                throw!(DeribitError::InvalidLeg(leg.instrument_name.clone()));
+            }
+        }
+        Self {
+            group: OrderGroup::new(label),
+            amount,
+            legs: legs
+                .into_iter()
+                .map(|leg| LegStatus {
+                    leg,
+                    order_id: None,
+                    order_state: None,
+                    filled_amount: 0.,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        self.group.label()
+    }
+
+    pub fn legs(&self) -> &[LegStatus] {
+        &self.legs
+    }
+
+    /// Sends the legs not placed yet.
+    #[throws(Error)]
+    pub async fn place(&mut self, client: &mut DeribitAPIClient) {
+        for status in self.legs.iter_mut().filter(|s| s.order_id.is_none()) {
+            let leg = &status.leg;
+            let amount = self.amount * leg.ratio;
+            let resp = match leg.direction {
+                Direction::Buy => {
+                    let mut req = BuyRequest::limit(&*leg.instrument_name, leg.price, amount);
+                    self.group.tag(req.deref_mut());
+                    client.call(req).await?.await?.0
+                }
+                Direction::Sell => {
+                    let mut req = SellRequest::limit(&*leg.instrument_name, leg.price, amount);
+                    self.group.tag(req.deref_mut());
+                    client.call(req).await?.await?.0
+                }
+                Direction::Zero => throw!(DeribitError::InvalidLeg(leg.instrument_name.clone())),
+            };
+            status.order_id = Some(resp.order.order_id.clone());
+            status.order_state = Some(resp.order.order_state);
+            status.filled_amount = resp.order.filled_amount.unwrap_or_default();
+        }
+    }
+
+    /// Polls the state of every placed leg.
+    #[throws(Error)]
+    pub async fn refresh(&mut self, client: &mut DeribitAPIClient) {
+        let ids: Vec<_> = self
+            .legs
+            .iter()
+            .filter_map(|s| s.order_id.clone())
+            .collect();
+        for id in ids {
+            let order = client.call(GetOrderStateRequest::new(&id)).await?.await?;
+            self.update(&order);
+        }
+    }
+
+    /// Cancels the open legs, returning the number of cancelled orders.
+    ///
+    /// The open legs are marked cancelled right away, a fill racing the cancellation is only
+    /// seen by the next update or `refresh`.
+    #[throws(Error)]
+    pub async fn cancel(&mut self, client: &mut DeribitAPIClient, currency: Currency) -> usize {
+        let cancelled = self.group.cancel(client, Some(currency)).await?;
+        for status in self.legs.iter_mut().filter(|s| s.order_id.is_some()) {
+            if !status.is_done() {
+                status.order_state = Some(OrderState::Cancelled);
+            }
+        }
+        cancelled
+    }
+
+    /// Applies an order update, ignoring orders not belonging to this structure.
+    pub fn update(&mut self, order: &Order) {
+        self.update_leg(
+            &order.order_id,
+            order.order_state,
+            order.filled_amount.unwrap_or_default(),
+        )
+    }
+
+    /// Applies an update from the `user.orders` channel.
+    pub fn update_user_order(&mut self, order: &UserOrdersData) {
+        self.update_leg(&order.order_id, order.order_state, order.filled_amount)
+    }
+
+    fn update_leg(&mut self, order_id: &str, order_state: OrderState, filled_amount: f64) {
+        if let Some(status) = self
+            .legs
+            .iter_mut()
+            .find(|s| s.order_id.as_deref() == Some(order_id))
+        {
+            status.order_state = Some(order_state);
+            status.filled_amount = filled_amount;
+        }
+    }
+
+    /// The number of complete structure units filled, limited by the least filled leg.
+    pub fn filled_amount(&self) -> f64 {
+        self.legs
+            .iter()
+            .map(|s| s.filled_amount / s.leg.ratio)
+            .fold(None, |min: Option<f64>, x| {
+                Some(min.map_or(x, |m| m.min(x)))
+            })
+            .unwrap_or_default()
+    }
+
+    /// The amount filled on each leg in excess of the complete structure units,
+    /// i.e. the legging exposure to hedge or unwind.
+    pub fn imbalance(&self) -> Vec<(&str, f64)> {
+        let filled = self.filled_amount();
+        self.legs
+            .iter()
+            .map(|s| {
+                let excess = s.filled_amount - filled * s.leg.ratio;
+                let sign = s.leg.direction.sign() as f64;
+                (s.leg.instrument_name.as_str(), sign * excess)
+            })
+            .filter(|(_, excess)| *excess != 0.)
+            .collect()
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.filled_amount() >= self.amount
+    }
+
+    /// Whether no leg is resting on the book anymore.
+    pub fn is_done(&self) -> bool {
+        self.legs.iter().all(LegStatus::is_done)
+    }
+}
@@ -1,9 +1,24 @@
 #![recursion_limit = "512"]
 
+pub mod announcement_watcher;
 mod api_client;
+pub mod block_trade_flow;
+pub mod candle_aggregator;
+pub mod currency_registry;
 pub mod errors;
+pub mod legged_order;
+pub mod mmp_guard;
 pub mod models;
+pub mod order_book;
+pub mod order_group;
+pub mod pricing;
+pub mod quote_set;
+pub mod rebalancer;
+pub mod risk;
+pub mod risk_limits;
 mod subscription_client;
+pub mod ticker_cache;
+pub mod withdrawal_guard;
 
 pub use crate::api_client::{DeribitAPICallRawResult, DeribitAPICallResult, DeribitAPIClient};
 pub use crate::errors::{DeribitError, Result};
@@ -31,8 +46,8 @@ lazy_static! {
 
 type WSStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
-pub const WS_URL: &'static str = "wss://www.deribit.com/ws/api/v2";
-pub const WS_URL_TESTNET: &'static str = "wss://test.deribit.com/ws/api/v2";
+pub const WS_URL: &str = "wss://www.deribit.com/ws/api/v2";
+pub const WS_URL_TESTNET: &str = "wss://test.deribit.com/ws/api/v2";
 
 #[derive(Default, Builder, Debug)]
 #[builder(setter(into))]
@@ -70,7 +85,7 @@ impl Deribit {
                     warn!("[Servo] Exiting because of '{}'", e)
                 }
             })
-            .then(|_| async { () });
+            .then(|_| async {});
 
         tokio::spawn(background);
 
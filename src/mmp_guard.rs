@@ -0,0 +1,92 @@
+use crate::models::subscription::UserMmpTriggerData;
+use crate::models::ResetMmpRequest;
+use crate::DeribitAPIClient;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use failure::Error;
+use fehler::throws;
+
+/// Tracks the market maker protection state of one index so that the quoting logic can
+/// pause once MMP fires, and resumes it by resetting MMP after a cooldown.
+///
+/// Feed it the `user.mmp_trigger.{currency}` notifications and poll `resume_if_due`
+/// from the quoting loop.
+#[derive(Debug, Clone)]
+pub struct MmpGuard {
+    index_name: String,
+    mmp_group: Option<String>,
+    cooldown: Duration,
+    triggered_at: Option<DateTime<Utc>>,
+    frozen_until: Option<DateTime<Utc>>,
+}
+
+impl MmpGuard {
+    pub fn new(index_name: &str, cooldown: Duration) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            mmp_group: None,
+            cooldown,
+            triggered_at: None,
+            frozen_until: None,
+        }
+    }
+
+    pub fn with_group(index_name: &str, mmp_group: &str, cooldown: Duration) -> Self {
+        Self {
+            mmp_group: Some(mmp_group.to_string()),
+            ..Self::new(index_name, cooldown)
+        }
+    }
+
+    /// Pauses quoting, ignoring triggers of other indices or MMP groups.
+    pub fn on_trigger(&mut self, data: &UserMmpTriggerData, now: DateTime<Utc>) {
+        if data
+            .index_name
+            .as_ref()
+            .map_or(false, |i| *i != self.index_name)
+            || data.mmp_group != self.mmp_group
+        {
+            return;
+        }
+        self.triggered_at = Some(now);
+        self.frozen_until = match data.frozen_until {
+            0 => None,
+            ts => Utc.timestamp_millis_opt(ts as i64).single(),
+        };
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.triggered_at.is_some()
+    }
+
+    /// When quoting may resume, the later of the end of the cooldown and of the freeze.
+    pub fn resume_at(&self) -> Option<DateTime<Utc>> {
+        let cooldown_end = self.triggered_at? + self.cooldown;
+        Some(
+            self.frozen_until
+                .map_or(cooldown_end, |f| f.max(cooldown_end)),
+        )
+    }
+
+    /// Resets MMP once the cooldown is over, returning whether quoting can resume.
+    #[throws(Error)]
+    pub async fn resume_if_due(
+        &mut self,
+        client: &mut DeribitAPIClient,
+        now: DateTime<Utc>,
+    ) -> bool {
+        match self.resume_at() {
+            None => true,
+            Some(resume_at) if resume_at <= now => {
+                let req = ResetMmpRequest {
+                    index_name: self.index_name.clone(),
+                    mmp_group: self.mmp_group.clone(),
+                };
+                client.call(req).await?.await?;
+                self.triggered_at = None;
+                self.frozen_until = None;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::models::subscription::AnnouncementsData;
 use crate::models::{AssetKind, Currency, Direction, Request};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -41,6 +42,8 @@ pub struct GetPositionsResponse {
     pub estimated_liquidation_price: Option<f64>,
     pub floating_profit_loss: f64,
     pub floating_profit_loss_usd: Option<f64>,
+    /// Only set on option positions
+    pub gamma: Option<f64>,
     pub index_price: f64,
     pub initial_margin: f64,
     pub instrument_name: String,
@@ -52,7 +55,9 @@ pub struct GetPositionsResponse {
     pub settlement_price: f64,
     pub size: f64,
     pub size_currency: Option<f64>,
+    pub theta: Option<f64>,
     pub total_profit_loss: f64,
+    pub vega: Option<f64>,
 }
 
 impl Request for GetPositionsRequest {
@@ -123,12 +128,54 @@ impl Request for GetAccountSummaryRequest {
     type Response = GetAccountSummaryResponse;
 }
 
+/// Computes the account summary as if the account held the `simulated_positions`, in
+/// addition to its current positions unless created with `replacing`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct SimulatePortfolioRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_positions: Option<bool>,
+    /// Signed position sizes by instrument name, negative for short positions
+    pub simulated_positions: HashMap<String, f64>,
+}
+
+impl SimulatePortfolioRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+
+    pub fn replacing(currency: Currency) -> Self {
+        Self {
+            currency,
+            add_positions: Some(false),
+            ..Default::default()
+        }
+    }
+
+    pub fn position(mut self, instrument_name: &str, size: f64) -> Self {
+        self.simulated_positions
+            .insert(instrument_name.to_string(), size);
+        self
+    }
+}
+
+pub type SimulatePortfolioResponse = GetAccountSummaryResponse;
+
+impl Request for SimulatePortfolioRequest {
+    const METHOD: &'static str = "private/simulate_portfolio";
+    type Response = SimulatePortfolioResponse;
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GetSubaccountsRequest {
     pub with_portfolio: bool,
 }
 
 impl GetSubaccountsRequest {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             with_portfolio: false,
@@ -173,3 +220,241 @@ impl Request for GetSubaccountsRequest {
     const METHOD: &'static str = "private/get_subaccounts";
     type Response = Vec<GetSubaccountsResponse>;
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CreateSubaccountRequest;
+
+pub type CreateSubaccountResponse = GetSubaccountsResponse;
+
+impl Request for CreateSubaccountRequest {
+    const METHOD: &'static str = "private/create_subaccount";
+    const HAS_PAYLOAD: bool = false;
+    type Response = CreateSubaccountResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChangeSubaccountNameRequest {
+    pub sid: u64,
+    pub name: String,
+}
+
+impl ChangeSubaccountNameRequest {
+    pub fn new(sid: u64, name: &str) -> Self {
+        Self {
+            sid,
+            name: name.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeSubaccountNameResponse {
+    Ok,
+}
+
+impl Request for ChangeSubaccountNameRequest {
+    const METHOD: &'static str = "private/change_subaccount_name";
+    type Response = ChangeSubaccountNameResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SetEmailForSubaccountRequest {
+    pub sid: u64,
+    pub email: String,
+}
+
+impl SetEmailForSubaccountRequest {
+    pub fn new(sid: u64, email: &str) -> Self {
+        Self {
+            sid,
+            email: email.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SetEmailForSubaccountResponse {
+    Ok,
+}
+
+impl Request for SetEmailForSubaccountRequest {
+    const METHOD: &'static str = "private/set_email_for_subaccount";
+    type Response = SetEmailForSubaccountResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SetPasswordForSubaccountRequest {
+    pub sid: u64,
+    pub password: String,
+}
+
+impl SetPasswordForSubaccountRequest {
+    pub fn new(sid: u64, password: &str) -> Self {
+        Self {
+            sid,
+            password: password.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SetPasswordForSubaccountResponse {
+    Ok,
+}
+
+impl Request for SetPasswordForSubaccountRequest {
+    const METHOD: &'static str = "private/set_password_for_subaccount";
+    type Response = SetPasswordForSubaccountResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubaccountLoginState {
+    Enable,
+    Disable,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleSubaccountLoginRequest {
+    pub sid: u64,
+    pub state: SubaccountLoginState,
+}
+
+impl ToggleSubaccountLoginRequest {
+    pub fn enable(sid: u64) -> Self {
+        Self {
+            sid,
+            state: SubaccountLoginState::Enable,
+        }
+    }
+
+    pub fn disable(sid: u64) -> Self {
+        Self {
+            sid,
+            state: SubaccountLoginState::Disable,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ToggleSubaccountLoginResponse {
+    Ok,
+}
+
+impl Request for ToggleSubaccountLoginRequest {
+    const METHOD: &'static str = "private/toggle_subaccount_login";
+    type Response = ToggleSubaccountLoginResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleNotificationsFromSubaccountRequest {
+    pub sid: u64,
+    pub state: bool,
+}
+
+impl ToggleNotificationsFromSubaccountRequest {
+    pub fn new(sid: u64, state: bool) -> Self {
+        Self { sid, state }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ToggleNotificationsFromSubaccountResponse {
+    Ok,
+}
+
+impl Request for ToggleNotificationsFromSubaccountRequest {
+    const METHOD: &'static str = "private/toggle_notifications_from_subaccount";
+    type Response = ToggleNotificationsFromSubaccountResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Announcement {
+    pub id: u64,
+    pub title: String,
+    pub body: String,
+    pub date: u64,
+    pub important: bool,
+    pub number: Option<u64>,
+}
+
+impl From<&AnnouncementsData> for Announcement {
+    fn from(data: &AnnouncementsData) -> Self {
+        Self {
+            id: data.id,
+            title: data.title.clone(),
+            body: data.body.clone(),
+            date: data.date,
+            important: data.important,
+            number: Some(data.number),
+        }
+    }
+}
+
+/// Announcements published before `start_timestamp`, the most recent first.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetAnnouncementsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_timestamp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+}
+
+impl GetAnnouncementsRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn before(start_timestamp: u64, count: u64) -> Self {
+        Self {
+            start_timestamp: Some(start_timestamp),
+            count: Some(count),
+        }
+    }
+}
+
+pub type GetAnnouncementsResponse = Vec<Announcement>;
+
+impl Request for GetAnnouncementsRequest {
+    const METHOD: &'static str = "public/get_announcements";
+    type Response = GetAnnouncementsResponse;
+}
+
+/// The announcements not marked as read yet.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetNewAnnouncementsRequest;
+
+pub type GetNewAnnouncementsResponse = Vec<Announcement>;
+
+impl Request for GetNewAnnouncementsRequest {
+    const METHOD: &'static str = "private/get_new_announcements";
+    const HAS_PAYLOAD: bool = false;
+    type Response = GetNewAnnouncementsResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SetAnnouncementAsReadRequest {
+    pub announcement_id: u64,
+}
+
+impl SetAnnouncementAsReadRequest {
+    pub fn new(announcement_id: u64) -> Self {
+        Self { announcement_id }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SetAnnouncementAsReadResponse {
+    Ok,
+}
+
+impl Request for SetAnnouncementAsReadRequest {
+    const METHOD: &'static str = "private/set_announcement_as_read";
+    type Response = SetAnnouncementAsReadResponse;
+}
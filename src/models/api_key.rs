@@ -0,0 +1,357 @@
+use crate::errors::DeribitError;
+use crate::models::Request;
+use fehler::{throw, throws};
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::result::Result as StdResult;
+use std::str::FromStr;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopeAccess {
+    None,
+    Read,
+    ReadWrite,
+}
+
+impl ScopeAccess {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScopeAccess::None => "none",
+            ScopeAccess::Read => "read",
+            ScopeAccess::ReadWrite => "read_write",
+        }
+    }
+}
+
+impl FromStr for ScopeAccess {
+    type Err = DeribitError;
+    #[throws(DeribitError)]
+    fn from_str(s: &str) -> ScopeAccess {
+        match s {
+            "none" => ScopeAccess::None,
+            "read" => ScopeAccess::Read,
+            "read_write" => ScopeAccess::ReadWrite,
+            _ => throw!(DeribitError::InvalidApiKeyScope(s.to_string())),
+        }
+    }
+}
+
+/// The access rights of an API key, sent and received by Deribit as a space separated
+/// string such as `account:read trade:read_write wallet:none`.
+///
+/// Sections left to `None` are not part of the scope, and the tokens which are not an
+/// access section, e.g. `mainaccount` or `ip:1.2.3.4`, are kept in `other`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ApiKeyScope {
+    pub account: Option<ScopeAccess>,
+    pub trade: Option<ScopeAccess>,
+    pub wallet: Option<ScopeAccess>,
+    pub block_trade: Option<ScopeAccess>,
+    pub custody: Option<ScopeAccess>,
+    pub other: Vec<String>,
+}
+
+impl ApiKeyScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read access to everything but the wallet.
+    pub fn read_only() -> Self {
+        Self::new()
+            .account(ScopeAccess::Read)
+            .trade(ScopeAccess::Read)
+            .wallet(ScopeAccess::None)
+    }
+
+    pub fn account(mut self, access: ScopeAccess) -> Self {
+        self.account = Some(access);
+        self
+    }
+
+    pub fn trade(mut self, access: ScopeAccess) -> Self {
+        self.trade = Some(access);
+        self
+    }
+
+    pub fn wallet(mut self, access: ScopeAccess) -> Self {
+        self.wallet = Some(access);
+        self
+    }
+
+    pub fn block_trade(mut self, access: ScopeAccess) -> Self {
+        self.block_trade = Some(access);
+        self
+    }
+
+    pub fn custody(mut self, access: ScopeAccess) -> Self {
+        self.custody = Some(access);
+        self
+    }
+
+    fn sections(&self) -> [(&'static str, Option<ScopeAccess>); 5] {
+        [
+            ("account", self.account),
+            ("trade", self.trade),
+            ("wallet", self.wallet),
+            ("block_trade", self.block_trade),
+            ("custody", self.custody),
+        ]
+    }
+}
+
+impl fmt::Display for ApiKeyScope {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let sections = self
+            .sections()
+            .iter()
+            .filter_map(|&(name, access)| Some(format!("{}:{}", name, access?.as_str())))
+            .collect::<Vec<_>>();
+        let tokens = sections.iter().chain(&self.other);
+        for (i, token) in tokens.enumerate() {
+            if i > 0 {
+                fmt.write_str(" ")?;
+            }
+            fmt.write_str(token)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = DeribitError;
+    #[throws(DeribitError)]
+    fn from_str(s: &str) -> ApiKeyScope {
+        let mut scope = ApiKeyScope::new();
+        for token in s.split_whitespace() {
+            let (name, access) = match token.split_once(':') {
+                Some((name, access)) => (name, access),
+                None => (token, ""),
+            };
+            let section = match name {
+                "account" => &mut scope.account,
+                "trade" => &mut scope.trade,
+                "wallet" => &mut scope.wallet,
+                "block_trade" => &mut scope.block_trade,
+                "custody" => &mut scope.custody,
+                _ => {
+                    scope.other.push(token.to_string());
+                    continue;
+                }
+            };
+            *section = Some(access.parse()?);
+        }
+        scope
+    }
+}
+
+impl Serialize for ApiKeyScope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ApiKeyScope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ApiKey {
+    pub id: u64,
+    pub name: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub max_scope: ApiKeyScope,
+    pub enabled: bool,
+    pub default: bool,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub enabled_features: Vec<String>,
+    pub public_key: Option<String>,
+    pub ip_whitelist: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CreateApiKeyRequest {
+    pub max_scope: ApiKeyScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// PEM encoded Ed25519 or RSA public key, for keys authenticating with signatures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+impl CreateApiKeyRequest {
+    pub fn new(max_scope: ApiKeyScope) -> Self {
+        Self {
+            max_scope,
+            name: None,
+            public_key: None,
+        }
+    }
+
+    pub fn with_name(max_scope: ApiKeyScope, name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            ..Self::new(max_scope)
+        }
+    }
+}
+
+pub type CreateApiKeyResponse = ApiKey;
+
+impl Request for CreateApiKeyRequest {
+    const METHOD: &'static str = "private/create_api_key";
+    type Response = CreateApiKeyResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ListApiKeysRequest;
+
+pub type ListApiKeysResponse = Vec<ApiKey>;
+
+impl Request for ListApiKeysRequest {
+    const METHOD: &'static str = "private/list_api_keys";
+    const HAS_PAYLOAD: bool = false;
+    type Response = ListApiKeysResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct EnableApiKeyRequest {
+    pub id: u64,
+}
+
+impl EnableApiKeyRequest {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+pub type EnableApiKeyResponse = ApiKey;
+
+impl Request for EnableApiKeyRequest {
+    const METHOD: &'static str = "private/enable_api_key";
+    type Response = EnableApiKeyResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DisableApiKeyRequest {
+    pub id: u64,
+}
+
+impl DisableApiKeyRequest {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+pub type DisableApiKeyResponse = ApiKey;
+
+impl Request for DisableApiKeyRequest {
+    const METHOD: &'static str = "private/disable_api_key";
+    type Response = DisableApiKeyResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RemoveApiKeyRequest {
+    pub id: u64,
+}
+
+impl RemoveApiKeyRequest {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoveApiKeyResponse {
+    Ok,
+}
+
+impl Request for RemoveApiKeyRequest {
+    const METHOD: &'static str = "private/remove_api_key";
+    type Response = RemoveApiKeyResponse;
+}
+
+/// Generates a new client secret for the key.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ResetApiKeyRequest {
+    pub id: u64,
+}
+
+impl ResetApiKeyRequest {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+pub type ResetApiKeyResponse = ApiKey;
+
+impl Request for ResetApiKeyRequest {
+    const METHOD: &'static str = "private/reset_api_key";
+    type Response = ResetApiKeyResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ChangeScopeInApiKeyRequest {
+    pub id: u64,
+    pub max_scope: ApiKeyScope,
+}
+
+impl ChangeScopeInApiKeyRequest {
+    pub fn new(id: u64, max_scope: ApiKeyScope) -> Self {
+        Self { id, max_scope }
+    }
+}
+
+pub type ChangeScopeInApiKeyResponse = ApiKey;
+
+impl Request for ChangeScopeInApiKeyRequest {
+    const METHOD: &'static str = "private/change_scope_in_api_key";
+    type Response = ChangeScopeInApiKeyResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ChangeApiKeyNameRequest {
+    pub id: u64,
+    pub name: String,
+}
+
+impl ChangeApiKeyNameRequest {
+    pub fn new(id: u64, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+        }
+    }
+}
+
+pub type ChangeApiKeyNameResponse = ApiKey;
+
+impl Request for ChangeApiKeyNameRequest {
+    const METHOD: &'static str = "private/change_api_key_name";
+    type Response = ChangeApiKeyNameResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SetApiKeyAsDefaultRequest {
+    pub id: u64,
+}
+
+impl SetApiKeyAsDefaultRequest {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+pub type SetApiKeyAsDefaultResponse = ApiKey;
+
+impl Request for SetApiKeyAsDefaultRequest {
+    const METHOD: &'static str = "private/set_api_key_as_default";
+    type Response = SetApiKeyAsDefaultResponse;
+}
@@ -0,0 +1,226 @@
+use crate::models::{Currency, Direction, Request, Trade};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NONCE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockTradeRole {
+    Maker,
+    Taker,
+}
+
+impl BlockTradeRole {
+    pub fn counterparty(self) -> BlockTradeRole {
+        match self {
+            BlockTradeRole::Maker => BlockTradeRole::Taker,
+            BlockTradeRole::Taker => BlockTradeRole::Maker,
+        }
+    }
+}
+
+/// One leg of a block trade, `direction` is always from the maker's perspective.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BlockTradeLeg {
+    pub instrument_name: String,
+    pub price: f64,
+    pub amount: f64,
+    pub direction: Direction,
+}
+
+impl BlockTradeLeg {
+    pub fn new(instrument_name: &str, price: f64, amount: f64, direction: Direction) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            price,
+            amount,
+            direction,
+        }
+    }
+}
+
+/// The terms of a block trade both parties have to agree on.
+///
+/// Both sides must use the very same `timestamp` and `nonce`: the first party calls
+/// `verify` and sends the resulting `SignedBlockTrade` to its counterparty, which executes it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BlockTradeProposal {
+    pub timestamp: u64,
+    pub nonce: String,
+    pub trades: Vec<BlockTradeLeg>,
+}
+
+impl BlockTradeProposal {
+    pub fn new(trades: Vec<BlockTradeLeg>) -> Self {
+        let now = Utc::now();
+        let counter = NONCE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self {
+            timestamp: now.timestamp_millis() as u64,
+            nonce: format!(
+                "{:x}{:04x}",
+                now.timestamp_nanos_opt().unwrap_or(0),
+                counter % 0x10000
+            ),
+            trades,
+        }
+    }
+
+    pub fn verify(&self, role: BlockTradeRole) -> VerifyBlockTradeRequest {
+        VerifyBlockTradeRequest {
+            timestamp: self.timestamp,
+            nonce: self.nonce.clone(),
+            role,
+            trades: self.trades.clone(),
+        }
+    }
+
+    /// Pairs the proposal with the signature returned by `VerifyBlockTradeRequest`
+    /// so that it can be handed over to the counterparty.
+    pub fn signed(self, role: BlockTradeRole, signature: String) -> SignedBlockTrade {
+        SignedBlockTrade {
+            proposal: self,
+            role,
+            signature,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SignedBlockTrade {
+    pub proposal: BlockTradeProposal,
+    /// The role of the party that signed
+    pub role: BlockTradeRole,
+    pub signature: String,
+}
+
+impl SignedBlockTrade {
+    /// The request the counterparty sends to execute the block trade.
+    pub fn execute(&self) -> ExecuteBlockTradeRequest {
+        ExecuteBlockTradeRequest {
+            timestamp: self.proposal.timestamp,
+            nonce: self.proposal.nonce.clone(),
+            role: self.role.counterparty(),
+            trades: self.proposal.trades.clone(),
+            counterparty_signature: self.signature.clone(),
+        }
+    }
+
+    /// The request the signing party sends to revoke its signature before execution.
+    pub fn invalidate(&self) -> InvalidateBlockTradeSignatureRequest {
+        InvalidateBlockTradeSignatureRequest::new(&self.signature)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct VerifyBlockTradeRequest {
+    pub timestamp: u64,
+    pub nonce: String,
+    pub role: BlockTradeRole,
+    pub trades: Vec<BlockTradeLeg>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct VerifyBlockTradeResponse {
+    pub signature: String,
+}
+
+impl Request for VerifyBlockTradeRequest {
+    const METHOD: &'static str = "private/verify_block_trade";
+    type Response = VerifyBlockTradeResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ExecuteBlockTradeRequest {
+    pub timestamp: u64,
+    pub nonce: String,
+    pub role: BlockTradeRole,
+    pub trades: Vec<BlockTradeLeg>,
+    pub counterparty_signature: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BlockTrade {
+    pub id: String,
+    pub timestamp: u64,
+    pub trades: Vec<Trade>,
+    pub executor_user_id: Option<u64>,
+    pub initiator_user_id: Option<u64>,
+}
+
+pub type ExecuteBlockTradeResponse = BlockTrade;
+
+impl Request for ExecuteBlockTradeRequest {
+    const METHOD: &'static str = "private/execute_block_trade";
+    type Response = ExecuteBlockTradeResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetBlockTradeRequest {
+    pub id: String,
+}
+
+impl GetBlockTradeRequest {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
+}
+
+pub type GetBlockTradeResponse = BlockTrade;
+
+impl Request for GetBlockTradeRequest {
+    const METHOD: &'static str = "private/get_block_trade";
+    type Response = GetBlockTradeResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastBlockTradesByCurrencyRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_id: Option<String>,
+}
+
+impl GetLastBlockTradesByCurrencyRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetLastBlockTradesByCurrencyResponse = Vec<BlockTrade>;
+
+impl Request for GetLastBlockTradesByCurrencyRequest {
+    const METHOD: &'static str = "private/get_last_block_trades_by_currency";
+    type Response = GetLastBlockTradesByCurrencyResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct InvalidateBlockTradeSignatureRequest {
+    pub signature: String,
+}
+
+impl InvalidateBlockTradeSignatureRequest {
+    pub fn new(signature: &str) -> Self {
+        Self {
+            signature: signature.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum InvalidateBlockTradeSignatureResponse {
+    Ok,
+}
+
+impl Request for InvalidateBlockTradeSignatureRequest {
+    const METHOD: &'static str = "private/invalidate_block_trade_signature";
+    type Response = InvalidateBlockTradeSignatureResponse;
+}
@@ -0,0 +1,141 @@
+use crate::models::{BuyRequest, Currency, Direction, Request, SellRequest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComboState {
+    Rfq,
+    Active,
+    Inactive,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ComboLeg {
+    pub instrument_name: String,
+    /// Signed ratio of the leg, negative for legs sold when buying the combo
+    pub amount: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Combo {
+    pub id: String,
+    pub instrument_id: u64,
+    pub creation_timestamp: u64,
+    pub state: ComboState,
+    pub state_timestamp: u64,
+    pub legs: Vec<ComboLeg>,
+}
+
+impl Combo {
+    /// Combos trade like any other instrument, with the combo id as instrument name.
+    pub fn buy(&self, price: f64, amount: f64) -> BuyRequest {
+        BuyRequest::limit(&*self.id, price, amount)
+    }
+
+    pub fn sell(&self, price: f64, amount: f64) -> SellRequest {
+        SellRequest::limit(&*self.id, price, amount)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetComboIdsRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<ComboState>,
+}
+
+impl GetComboIdsRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            state: None,
+        }
+    }
+
+    pub fn active(currency: Currency) -> Self {
+        Self {
+            currency,
+            state: Some(ComboState::Active),
+        }
+    }
+}
+
+pub type GetComboIdsResponse = Vec<String>;
+
+impl Request for GetComboIdsRequest {
+    const METHOD: &'static str = "public/get_combo_ids";
+    type Response = GetComboIdsResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetComboDetailsRequest {
+    pub combo_id: String,
+}
+
+impl GetComboDetailsRequest {
+    pub fn new(combo_id: &str) -> Self {
+        Self {
+            combo_id: combo_id.to_string(),
+        }
+    }
+}
+
+pub type GetComboDetailsResponse = Combo;
+
+impl Request for GetComboDetailsRequest {
+    const METHOD: &'static str = "public/get_combo_details";
+    type Response = GetComboDetailsResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetCombosRequest {
+    pub currency: Currency,
+}
+
+impl GetCombosRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self { currency }
+    }
+}
+
+pub type GetCombosResponse = Vec<Combo>;
+
+impl Request for GetCombosRequest {
+    const METHOD: &'static str = "public/get_combos";
+    type Response = GetCombosResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CreateComboTrade {
+    pub instrument_name: String,
+    pub amount: f64,
+    pub direction: Direction,
+}
+
+/// Creates the combo made of the given legs, or returns the existing one with the same legs.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct CreateComboRequest {
+    pub trades: Vec<CreateComboTrade>,
+}
+
+impl CreateComboRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn leg(mut self, instrument_name: &str, amount: f64, direction: Direction) -> Self {
+        self.trades.push(CreateComboTrade {
+            instrument_name: instrument_name.to_string(),
+            amount,
+            direction,
+        });
+        self
+    }
+}
+
+pub type CreateComboResponse = Combo;
+
+impl Request for CreateComboRequest {
+    const METHOD: &'static str = "private/create_combo";
+    type Response = CreateComboResponse;
+}
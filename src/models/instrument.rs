@@ -0,0 +1,145 @@
+use crate::errors::DeribitError;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use fehler::{throw, throws};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::result::Result as StdResult;
+use std::str::FromStr;
+
+/// Deribit settles futures and options at 08:00 UTC on the expiry date.
+pub const EXPIRY_HOUR_UTC: u32 = 8;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+impl OptionType {
+    pub fn sign(self) -> f64 {
+        match self {
+            OptionType::Call => 1.,
+            OptionType::Put => -1.,
+        }
+    }
+}
+
+/// A parsed instrument name, e.g. `BTC-PERPETUAL`, `ETH-28JUN19` or `BTC-28JUN19-3000-P`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstrumentName {
+    Perpetual {
+        underlying: String,
+    },
+    Future {
+        underlying: String,
+        expiry: NaiveDate,
+    },
+    Option {
+        underlying: String,
+        expiry: NaiveDate,
+        strike: f64,
+        option_type: OptionType,
+    },
+}
+
+impl InstrumentName {
+    pub fn underlying(&self) -> &str {
+        match self {
+            InstrumentName::Perpetual { underlying }
+            | InstrumentName::Future { underlying, .. }
+            | InstrumentName::Option { underlying, .. } => underlying,
+        }
+    }
+
+    /// The settlement time of the instrument, `None` for perpetuals.
+    pub fn expiry(&self) -> Option<DateTime<Utc>> {
+        match self {
+            InstrumentName::Perpetual { .. } => None,
+            InstrumentName::Future { expiry, .. } | InstrumentName::Option { expiry, .. } => {
+                Some(Utc.from_utc_datetime(&expiry.and_hms_opt(EXPIRY_HOUR_UTC, 0, 0)?))
+            }
+        }
+    }
+
+    pub fn strike(&self) -> Option<f64> {
+        match self {
+            InstrumentName::Option { strike, .. } => Some(*strike),
+            _ => None,
+        }
+    }
+
+    pub fn option_type(&self) -> Option<OptionType> {
+        match self {
+            InstrumentName::Option { option_type, .. } => Some(*option_type),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for InstrumentName {
+    type Err = DeribitError;
+
+    #[throws(DeribitError)]
+    fn from_str(s: &str) -> InstrumentName {
+        let invalid = || DeribitError::InvalidInstrumentName(s.to_string());
+        let segments: Vec<_> = s.split('-').collect();
+        match segments.as_slice() {
+            [underlying, "PERPETUAL"] => InstrumentName::Perpetual {
+                underlying: underlying.to_string(),
+            },
+            [underlying, expiry] => InstrumentName::Future {
+                underlying: underlying.to_string(),
+                expiry: parse_expiry(expiry).ok_or_else(invalid)?,
+            },
+            [underlying, expiry, strike, option_type] => InstrumentName::Option {
+                underlying: underlying.to_string(),
+                expiry: parse_expiry(expiry).ok_or_else(invalid)?,
+                // Fractional strikes are written with a `d`, e.g. `0d625`
+                strike: strike.replace('d', ".").parse().map_err(|_| invalid())?,
+                option_type: match *option_type {
+                    "C" => OptionType::Call,
+                    "P" => OptionType::Put,
+                    _ => throw!(invalid()),
+                },
+            },
+            _ => throw!(invalid()),
+        }
+    }
+}
+
+impl Display for InstrumentName {
+    fn fmt(&self, f: &mut Formatter) -> StdResult<(), FmtError> {
+        match self {
+            InstrumentName::Perpetual { underlying } => write!(f, "{}-PERPETUAL", underlying),
+            InstrumentName::Future { underlying, expiry } => {
+                write!(f, "{}-{}", underlying, format_expiry(*expiry))
+            }
+            InstrumentName::Option {
+                underlying,
+                expiry,
+                strike,
+                option_type,
+            } => write!(
+                f,
+                "{}-{}-{}-{}",
+                underlying,
+                format_expiry(*expiry),
+                strike.to_string().replace('.', "d"),
+                match option_type {
+                    OptionType::Call => "C",
+                    OptionType::Put => "P",
+                }
+            ),
+        }
+    }
+}
+
+// Expiries look like `28JUN19` or `5JUL19`
+fn parse_expiry(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{:0>7}", s), "%d%b%y").ok()
+}
+
+fn format_expiry(date: NaiveDate) -> String {
+    date.format("%-d%b%y").to_string().to_uppercase()
+}
@@ -1,5 +1,13 @@
-use crate::models::{AssetKind, Currency, Request};
-use chrono::{DateTime, Utc};
+use crate::errors::DeribitError;
+use crate::models::subscription::{ChartTradesData, TickerData, TradesData};
+use crate::models::trading::next_continuation;
+use crate::models::{
+    AssetKind, ChunkedRequest, Currency, GetSettlementHistoryResponse, PaginatedRequest, Request,
+    Settlement, SettlementType, Sorting,
+};
+use chrono::{DateTime, Duration, Utc};
+use failure::Error;
+use fehler::{throw, throws};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -172,3 +180,730 @@ impl Request for GetFundingRateValueRequest {
     const METHOD: &'static str = "public/get_funding_rate_value";
     type Response = GetFundingRateValueResponse;
 }
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetBookSummaryByInstrumentRequest {
+    pub instrument_name: String,
+}
+
+impl GetBookSummaryByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+        }
+    }
+}
+
+pub type GetBookSummaryByInstrumentResponse = Vec<GetBookSummaryByCurrencyResponse>;
+
+impl Request for GetBookSummaryByInstrumentRequest {
+    const METHOD: &'static str = "public/get_book_summary_by_instrument";
+    type Response = GetBookSummaryByInstrumentResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetOrderBookRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u64>,
+}
+
+impl GetOrderBookRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            depth: None,
+        }
+    }
+
+    pub fn with_depth(instrument_name: &str, depth: u64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            depth: Some(depth),
+        }
+    }
+}
+
+/// A snapshot of the book, `change_id` is the one the following `book.*` deltas chain from.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetOrderBookResponse {
+    pub asks: Vec<(f64, f64)>,
+    pub bids: Vec<(f64, f64)>,
+    pub change_id: i64,
+    #[serde(flatten)]
+    pub ticker: TickerData,
+}
+
+impl Request for GetOrderBookRequest {
+    const METHOD: &'static str = "public/get_order_book";
+    type Response = GetOrderBookResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TickerRequest {
+    pub instrument_name: String,
+}
+
+impl TickerRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+        }
+    }
+}
+
+pub type TickerResponse = TickerData;
+
+impl Request for TickerRequest {
+    const METHOD: &'static str = "public/ticker";
+    type Response = TickerResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetLastTradesResponse {
+    pub trades: Vec<TradesData>,
+    pub has_more: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastTradesByInstrumentRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetLastTradesByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetLastTradesByInstrumentResponse = GetLastTradesResponse;
+
+impl Request for GetLastTradesByInstrumentRequest {
+    const METHOD: &'static str = "public/get_last_trades_by_instrument";
+    type Response = GetLastTradesByInstrumentResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastTradesByInstrumentAndTimeRequest {
+    pub instrument_name: String,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetLastTradesByInstrumentAndTimeRequest {
+    pub fn new(instrument_name: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetLastTradesByInstrumentAndTimeResponse = GetLastTradesResponse;
+
+impl Request for GetLastTradesByInstrumentAndTimeRequest {
+    const METHOD: &'static str = "public/get_last_trades_by_instrument_and_time";
+    type Response = GetLastTradesByInstrumentAndTimeResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastTradesByCurrencyRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetLastTradesByCurrencyRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_kind(currency: Currency, kind: AssetKind) -> Self {
+        Self {
+            currency,
+            kind: Some(kind),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetLastTradesByCurrencyResponse = GetLastTradesResponse;
+
+impl Request for GetLastTradesByCurrencyRequest {
+    const METHOD: &'static str = "public/get_last_trades_by_currency";
+    type Response = GetLastTradesByCurrencyResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastTradesByCurrencyAndTimeRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetLastTradesByCurrencyAndTimeRequest {
+    pub fn new(currency: Currency, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            currency,
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetLastTradesByCurrencyAndTimeResponse = GetLastTradesResponse;
+
+impl Request for GetLastTradesByCurrencyAndTimeRequest {
+    const METHOD: &'static str = "public/get_last_trades_by_currency_and_time";
+    type Response = GetLastTradesByCurrencyAndTimeResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetFundingRateHistoryRequest {
+    pub instrument_name: String,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+}
+
+impl GetFundingRateHistoryRequest {
+    pub fn new(instrument_name: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct FundingRate {
+    pub timestamp: u64,
+    pub index_price: f64,
+    pub prev_index_price: f64,
+    pub interest_8h: f64,
+    pub interest_1h: f64,
+}
+
+pub type GetFundingRateHistoryResponse = Vec<FundingRate>;
+
+impl Request for GetFundingRateHistoryRequest {
+    const METHOD: &'static str = "public/get_funding_rate_history";
+    type Response = GetFundingRateHistoryResponse;
+}
+
+impl ChunkedRequest for GetFundingRateHistoryRequest {
+    type Item = FundingRate;
+
+    fn max_window(&self) -> Duration {
+        Duration::days(30)
+    }
+
+    fn range(&self) -> (u64, u64) {
+        (self.start_timestamp, self.end_timestamp)
+    }
+
+    fn with_range(&self, start_timestamp: u64, end_timestamp: u64) -> Self {
+        Self {
+            start_timestamp,
+            end_timestamp,
+            ..self.clone()
+        }
+    }
+
+    #[throws(Error)]
+    fn items(rates: Vec<FundingRate>) -> Vec<FundingRate> {
+        rates
+    }
+
+    fn timestamp(rate: &FundingRate) -> u64 {
+        rate.timestamp
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum FundingChartLength {
+    #[serde(rename = "8h")]
+    EightHours,
+    #[serde(rename = "24h")]
+    OneDay,
+    #[serde(rename = "1m")]
+    OneMonth,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetFundingChartDataRequest {
+    pub instrument_name: String,
+    pub length: FundingChartLength,
+}
+
+impl GetFundingChartDataRequest {
+    pub fn new(instrument_name: &str, length: FundingChartLength) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            length,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct FundingChartPoint {
+    pub index_price: f64,
+    pub interest_8h: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetFundingChartDataResponse {
+    pub current_interest: f64,
+    pub interest_8h: f64,
+    pub data: Vec<FundingChartPoint>,
+}
+
+impl Request for GetFundingChartDataRequest {
+    const METHOD: &'static str = "public/get_funding_chart_data";
+    type Response = GetFundingChartDataResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetHistoricalVolatilityRequest {
+    pub currency: Currency,
+}
+
+impl GetHistoricalVolatilityRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self { currency }
+    }
+}
+
+/// `(timestamp, volatility)` pairs, the volatility in percent.
+pub type GetHistoricalVolatilityResponse = Vec<(u64, f64)>;
+
+impl Request for GetHistoricalVolatilityRequest {
+    const METHOD: &'static str = "public/get_historical_volatility";
+    type Response = GetHistoricalVolatilityResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    #[serde(rename = "1")]
+    OneMinute,
+    #[serde(rename = "3")]
+    ThreeMinutes,
+    #[serde(rename = "5")]
+    FiveMinutes,
+    #[serde(rename = "10")]
+    TenMinutes,
+    #[serde(rename = "15")]
+    FifteenMinutes,
+    #[serde(rename = "30")]
+    ThirtyMinutes,
+    #[serde(rename = "60")]
+    OneHour,
+    #[serde(rename = "120")]
+    TwoHours,
+    #[serde(rename = "180")]
+    ThreeHours,
+    #[serde(rename = "360")]
+    SixHours,
+    #[serde(rename = "720")]
+    TwelveHours,
+    #[serde(rename = "1D")]
+    OneDay,
+}
+
+impl Resolution {
+    pub fn duration(self) -> Duration {
+        Duration::minutes(match self {
+            Resolution::OneMinute => 1,
+            Resolution::ThreeMinutes => 3,
+            Resolution::FiveMinutes => 5,
+            Resolution::TenMinutes => 10,
+            Resolution::FifteenMinutes => 15,
+            Resolution::ThirtyMinutes => 30,
+            Resolution::OneHour => 60,
+            Resolution::TwoHours => 120,
+            Resolution::ThreeHours => 180,
+            Resolution::SixHours => 360,
+            Resolution::TwelveHours => 720,
+            Resolution::OneDay => 1440,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetTradingviewChartDataRequest {
+    pub instrument_name: String,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub resolution: Resolution,
+}
+
+impl GetTradingviewChartDataRequest {
+    pub fn new(
+        instrument_name: &str,
+        resolution: Resolution,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            resolution,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    pub timestamp: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub cost: f64,
+}
+
+impl From<&ChartTradesData> for Candle {
+    fn from(data: &ChartTradesData) -> Candle {
+        Candle {
+            timestamp: data.tick,
+            open: data.open,
+            high: data.high,
+            low: data.low,
+            close: data.close,
+            volume: data.volume,
+            cost: data.cost,
+        }
+    }
+}
+
+/// OHLCV columns, `status` is `no_data` when the range holds no candle.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetTradingviewChartDataResponse {
+    pub status: String,
+    pub ticks: Vec<u64>,
+    pub open: Vec<f64>,
+    pub high: Vec<f64>,
+    pub low: Vec<f64>,
+    pub close: Vec<f64>,
+    pub volume: Vec<f64>,
+    pub cost: Vec<f64>,
+}
+
+impl GetTradingviewChartDataResponse {
+    /// The candles of the columns, which must all have the same length.
+    #[throws(DeribitError)]
+    pub fn candles(&self) -> Vec<Candle> {
+        let len = self.ticks.len();
+        let columns = [
+            &self.open,
+            &self.high,
+            &self.low,
+            &self.close,
+            &self.volume,
+            &self.cost,
+        ];
+        if columns.iter().any(|column| column.len() != len) {
+            throw!(DeribitError::InvalidChartData);
+        }
+        (0..len)
+            .map(|i| Candle {
+                timestamp: self.ticks[i],
+                open: self.open[i],
+                high: self.high[i],
+                low: self.low[i],
+                close: self.close[i],
+                volume: self.volume[i],
+                cost: self.cost[i],
+            })
+            .collect()
+    }
+}
+
+impl Request for GetTradingviewChartDataRequest {
+    const METHOD: &'static str = "public/get_tradingview_chart_data";
+    type Response = GetTradingviewChartDataResponse;
+}
+
+impl ChunkedRequest for GetTradingviewChartDataRequest {
+    type Item = Candle;
+
+    fn max_window(&self) -> Duration {
+        self.resolution.duration() * 1000
+    }
+
+    fn range(&self) -> (u64, u64) {
+        (self.start_timestamp, self.end_timestamp)
+    }
+
+    fn with_range(&self, start_timestamp: u64, end_timestamp: u64) -> Self {
+        Self {
+            start_timestamp,
+            end_timestamp,
+            ..self.clone()
+        }
+    }
+
+    #[throws(Error)]
+    fn items(resp: GetTradingviewChartDataResponse) -> Vec<Candle> {
+        resp.candles()?
+    }
+
+    fn timestamp(candle: &Candle) -> u64 {
+        candle.timestamp
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastSettlementsByCurrencyRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<SettlementType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_start_timestamp: Option<u64>,
+}
+
+impl GetLastSettlementsByCurrencyRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetLastSettlementsByCurrencyResponse = GetSettlementHistoryResponse;
+
+impl Request for GetLastSettlementsByCurrencyRequest {
+    const METHOD: &'static str = "public/get_last_settlements_by_currency";
+    type Response = GetLastSettlementsByCurrencyResponse;
+}
+
+impl PaginatedRequest for GetLastSettlementsByCurrencyRequest {
+    type Item = Settlement;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetSettlementHistoryResponse) -> (Vec<Settlement>, Option<Self>) {
+        let next = next_continuation(resp.continuation, resp.settlements.len()).map(|c| Self {
+            continuation: Some(c),
+            ..self.clone()
+        });
+        (resp.settlements, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastSettlementsByInstrumentRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<SettlementType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_start_timestamp: Option<u64>,
+}
+
+impl GetLastSettlementsByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetLastSettlementsByInstrumentResponse = GetSettlementHistoryResponse;
+
+impl Request for GetLastSettlementsByInstrumentRequest {
+    const METHOD: &'static str = "public/get_last_settlements_by_instrument";
+    type Response = GetLastSettlementsByInstrumentResponse;
+}
+
+impl PaginatedRequest for GetLastSettlementsByInstrumentRequest {
+    type Item = Settlement;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetSettlementHistoryResponse) -> (Vec<Settlement>, Option<Self>) {
+        let next = next_continuation(resp.continuation, resp.settlements.len()).map(|c| Self {
+            continuation: Some(c),
+            ..self.clone()
+        });
+        (resp.settlements, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetDeliveryPricesRequest {
+    pub index_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+}
+
+impl GetDeliveryPricesRequest {
+    pub fn new(index_name: &str) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DeliveryPrice {
+    /// The delivery date as `YYYY-MM-DD`
+    pub date: String,
+    pub delivery_price: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetDeliveryPricesResponse {
+    pub data: Vec<DeliveryPrice>,
+    pub records_total: u64,
+}
+
+impl Request for GetDeliveryPricesRequest {
+    const METHOD: &'static str = "public/get_delivery_prices";
+    type Response = GetDeliveryPricesResponse;
+}
+
+impl PaginatedRequest for GetDeliveryPricesRequest {
+    type Item = DeliveryPrice;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetDeliveryPricesResponse) -> (Vec<DeliveryPrice>, Option<Self>) {
+        let offset = self.offset.unwrap_or(0) + resp.data.len() as u64;
+        let next = Some(Self {
+            offset: Some(offset),
+            ..self.clone()
+        })
+        .filter(|_| !resp.data.is_empty() && offset < resp.records_total);
+        (resp.data, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetTradeVolumesRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended: Option<bool>,
+}
+
+impl GetTradeVolumesRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Includes the 7 and 30 days volumes.
+    pub fn extended() -> Self {
+        Self {
+            extended: Some(true),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TradeVolume {
+    pub currency: Currency,
+    pub calls_volume: f64,
+    pub puts_volume: f64,
+    pub futures_volume: f64,
+    pub spot_volume: Option<f64>,
+    pub calls_volume_7d: Option<f64>,
+    pub puts_volume_7d: Option<f64>,
+    pub futures_volume_7d: Option<f64>,
+    pub spot_volume_7d: Option<f64>,
+    pub calls_volume_30d: Option<f64>,
+    pub puts_volume_30d: Option<f64>,
+    pub futures_volume_30d: Option<f64>,
+    pub spot_volume_30d: Option<f64>,
+}
+
+pub type GetTradeVolumesResponse = Vec<TradeVolume>;
+
+impl Request for GetTradeVolumesRequest {
+    const METHOD: &'static str = "public/get_trade_volumes";
+    type Response = GetTradeVolumesResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetCurrenciesRequest;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct WithdrawalPriority {
+    pub name: String,
+    pub value: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CurrencyInfo {
+    pub currency: Currency,
+    pub currency_long: String,
+    pub coin_type: String,
+    /// Number of decimals of the fees
+    pub fee_precision: u32,
+    pub min_confirmations: u32,
+    pub min_withdrawal_fee: f64,
+    pub withdrawal_fee: f64,
+    #[serde(default)]
+    pub withdrawal_priorities: Vec<WithdrawalPriority>,
+}
+
+pub type GetCurrenciesResponse = Vec<CurrencyInfo>;
+
+impl Request for GetCurrenciesRequest {
+    const METHOD: &'static str = "public/get_currencies";
+    const HAS_PAYLOAD: bool = false;
+    type Response = GetCurrenciesResponse;
+}
@@ -0,0 +1,189 @@
+use crate::models::{AssetKind, Currency, Order, Request};
+use serde::{Deserialize, Serialize};
+use shrinkwraprs::Shrinkwrap;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteSide {
+    Bid,
+    Ask,
+}
+
+/// One side of a quote, a zero `amount` removes the live quote on that side.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MassQuoteLevel {
+    pub price: f64,
+    pub amount: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_post_only: Option<bool>,
+}
+
+impl MassQuoteLevel {
+    pub fn new(price: f64, amount: f64) -> Self {
+        Self {
+            price,
+            amount,
+            post_only: None,
+            reject_post_only: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MassQuoteItem {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_set_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bid: Option<MassQuoteLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ask: Option<MassQuoteLevel>,
+}
+
+/// Places or replaces the quotes of many instruments at once. All the quotes are
+/// MMP quotes of `mmp_group`, and `quote_id` has to be unique for every request.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct MassQuoteRequest {
+    pub quote_id: String,
+    pub mmp_group: String,
+    pub quotes: Vec<MassQuoteItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_for_response: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detailed: Option<bool>,
+}
+
+impl MassQuoteRequest {
+    pub fn new(quote_id: &str, mmp_group: &str, quotes: Vec<MassQuoteItem>) -> Self {
+        Self {
+            quote_id: quote_id.to_string(),
+            mmp_group: mmp_group.to_string(),
+            quotes,
+            valid_until: None,
+            wait_for_response: None,
+            detailed: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MassQuoteError {
+    pub instrument_name: String,
+    pub side: QuoteSide,
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct MassQuoteResponse {
+    #[serde(default)]
+    pub orders: Vec<Order>,
+    #[serde(default)]
+    pub errors: Vec<MassQuoteError>,
+}
+
+impl Request for MassQuoteRequest {
+    const METHOD: &'static str = "private/mass_quote";
+    type Response = MassQuoteResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelQuotesType {
+    All,
+    ByCurrency,
+    ByKind,
+    ByInstrument,
+    ByQuoteSetId,
+    Delta,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CancelQuotesRequest {
+    pub cancel_type: CancelQuotesType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_set_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instrument_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_delta: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_delta: Option<f64>,
+    /// Prevents new quotes until they are explicitly re-enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freeze_quotes: Option<bool>,
+}
+
+impl CancelQuotesRequest {
+    fn new(cancel_type: CancelQuotesType) -> Self {
+        Self {
+            cancel_type,
+            quote_set_id: None,
+            currency: None,
+            kind: None,
+            instrument_name: None,
+            min_delta: None,
+            max_delta: None,
+            freeze_quotes: None,
+        }
+    }
+
+    pub fn all() -> Self {
+        Self::new(CancelQuotesType::All)
+    }
+
+    pub fn by_currency(currency: Currency) -> Self {
+        Self {
+            currency: Some(currency),
+            ..Self::new(CancelQuotesType::ByCurrency)
+        }
+    }
+
+    pub fn by_kind(currency: Currency, kind: AssetKind) -> Self {
+        Self {
+            currency: Some(currency),
+            kind: Some(kind),
+            ..Self::new(CancelQuotesType::ByKind)
+        }
+    }
+
+    pub fn by_instrument(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: Some(instrument_name.to_string()),
+            ..Self::new(CancelQuotesType::ByInstrument)
+        }
+    }
+
+    pub fn by_quote_set_id(quote_set_id: &str) -> Self {
+        Self {
+            quote_set_id: Some(quote_set_id.to_string()),
+            ..Self::new(CancelQuotesType::ByQuoteSetId)
+        }
+    }
+
+    /// Cancels the quotes of options whose delta lies within `[min_delta, max_delta]`.
+    pub fn by_delta(currency: Currency, min_delta: f64, max_delta: f64) -> Self {
+        Self {
+            currency: Some(currency),
+            min_delta: Some(min_delta),
+            max_delta: Some(max_delta),
+            ..Self::new(CancelQuotesType::Delta)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Shrinkwrap)]
+pub struct CancelQuotesResponse(pub usize);
+
+impl Request for CancelQuotesRequest {
+    const METHOD: &'static str = "private/cancel_quotes";
+    type Response = CancelQuotesResponse;
+}
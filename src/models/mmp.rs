@@ -0,0 +1,119 @@
+use crate::models::Request;
+use serde::{Deserialize, Serialize};
+
+/// Market maker protection limits of an index, e.g. `btc_usd`.
+///
+/// MMP freezes the MMP flagged orders of the account for `frozen_time` seconds once the
+/// limits are breached within `interval` seconds. A `frozen_time` of 0 keeps them frozen
+/// until `ResetMmpRequest` is sent.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MmpConfig {
+    pub index_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mmp_group: Option<String>,
+    /// Monitoring window in seconds, 0 disables MMP
+    pub interval: u64,
+    pub frozen_time: u64,
+    pub quantity_limit: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta_limit: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vega_limit: Option<f64>,
+}
+
+impl MmpConfig {
+    pub fn new(index_name: &str, interval: u64, frozen_time: u64, quantity_limit: f64) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            mmp_group: None,
+            interval,
+            frozen_time,
+            quantity_limit,
+            delta_limit: None,
+            vega_limit: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SetMmpConfigRequest(pub MmpConfig);
+
+impl SetMmpConfigRequest {
+    pub fn new(config: MmpConfig) -> Self {
+        Self(config)
+    }
+
+    /// Disables MMP on the index by setting a zero interval.
+    pub fn disable(index_name: &str) -> Self {
+        Self(MmpConfig::new(index_name, 0, 0, 0.))
+    }
+}
+
+pub type SetMmpConfigResponse = Vec<MmpConfig>;
+
+impl Request for SetMmpConfigRequest {
+    const METHOD: &'static str = "private/set_mmp_config";
+    type Response = SetMmpConfigResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetMmpConfigRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mmp_group: Option<String>,
+}
+
+impl GetMmpConfigRequest {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn new(index_name: &str) -> Self {
+        Self {
+            index_name: Some(index_name.to_string()),
+            mmp_group: None,
+        }
+    }
+}
+
+pub type GetMmpConfigResponse = Vec<MmpConfig>;
+
+impl Request for GetMmpConfigRequest {
+    const METHOD: &'static str = "private/get_mmp_config";
+    type Response = GetMmpConfigResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ResetMmpRequest {
+    pub index_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mmp_group: Option<String>,
+}
+
+impl ResetMmpRequest {
+    pub fn new(index_name: &str) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            mmp_group: None,
+        }
+    }
+
+    pub fn with_group(index_name: &str, mmp_group: &str) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            mmp_group: Some(mmp_group.to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetMmpResponse {
+    Ok,
+}
+
+impl Request for ResetMmpRequest {
+    const METHOD: &'static str = "private/reset_mmp";
+    type Response = ResetMmpResponse;
+}
@@ -1,7 +1,14 @@
 pub mod account;
+pub mod api_key;
 pub mod authentication;
+pub mod block_trading;
+pub mod combo;
+pub mod instrument;
 pub mod jsonrpc;
 pub mod market_data;
+pub mod mass_quote;
+pub mod mmp;
+pub mod order_builder;
 pub mod session_management;
 pub mod subscription;
 pub mod support;
@@ -9,23 +16,76 @@ pub mod trading;
 pub mod wallet;
 
 use crate::errors::DeribitError;
-use fehler::throws;
-use serde::{Deserialize, Serialize};
+use fehler::{throw, throws};
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::from_str;
 use std::fmt::{Display, Error as FmtError, Formatter};
 use std::result::Result as StdResult;
 
 pub use account::{
-    GetAccountSummaryRequest, GetAccountSummaryResponse, GetPositionsRequest, GetPositionsResponse,
-    GetSubaccountsRequest, GetSubaccountsResponse,
+    Announcement, ChangeSubaccountNameRequest, ChangeSubaccountNameResponse,
+    CreateSubaccountRequest, CreateSubaccountResponse, GetAccountSummaryRequest,
+    GetAccountSummaryResponse, GetAnnouncementsRequest, GetAnnouncementsResponse,
+    GetNewAnnouncementsRequest, GetNewAnnouncementsResponse, GetPositionsRequest,
+    GetPositionsResponse, GetSubaccountsRequest, GetSubaccountsResponse,
+    SetAnnouncementAsReadRequest, SetAnnouncementAsReadResponse, SetEmailForSubaccountRequest,
+    SetEmailForSubaccountResponse, SetPasswordForSubaccountRequest,
+    SetPasswordForSubaccountResponse, SimulatePortfolioRequest, SimulatePortfolioResponse,
+    SubaccountLoginState, ToggleNotificationsFromSubaccountRequest,
+    ToggleNotificationsFromSubaccountResponse, ToggleSubaccountLoginRequest,
+    ToggleSubaccountLoginResponse,
+};
+pub use api_key::{
+    ApiKey, ApiKeyScope, ChangeApiKeyNameRequest, ChangeApiKeyNameResponse,
+    ChangeScopeInApiKeyRequest, ChangeScopeInApiKeyResponse, CreateApiKeyRequest,
+    CreateApiKeyResponse, DisableApiKeyRequest, DisableApiKeyResponse, EnableApiKeyRequest,
+    EnableApiKeyResponse, ListApiKeysRequest, ListApiKeysResponse, RemoveApiKeyRequest,
+    RemoveApiKeyResponse, ResetApiKeyRequest, ResetApiKeyResponse, ScopeAccess,
+    SetApiKeyAsDefaultRequest, SetApiKeyAsDefaultResponse,
 };
 pub use authentication::{AuthRequest, AuthResponse, GrantType};
+pub use block_trading::{
+    BlockTrade, BlockTradeLeg, BlockTradeProposal, BlockTradeRole, ExecuteBlockTradeRequest,
+    ExecuteBlockTradeResponse, GetBlockTradeRequest, GetBlockTradeResponse,
+    GetLastBlockTradesByCurrencyRequest, GetLastBlockTradesByCurrencyResponse,
+    InvalidateBlockTradeSignatureRequest, InvalidateBlockTradeSignatureResponse, SignedBlockTrade,
+    VerifyBlockTradeRequest, VerifyBlockTradeResponse,
+};
+pub use combo::{
+    Combo, ComboLeg, ComboState, CreateComboRequest, CreateComboResponse, CreateComboTrade,
+    GetComboDetailsRequest, GetComboDetailsResponse, GetComboIdsRequest, GetComboIdsResponse,
+    GetCombosRequest, GetCombosResponse,
+};
+pub use instrument::{InstrumentName, OptionType};
 pub use jsonrpc::{JSONRPCRequest, JSONRPCResponse, JSONRPCVersion};
 pub use market_data::{
-    GetBookSummaryByCurrencyRequest, GetBookSummaryByCurrencyResponse, GetFundingRateValueRequest,
-    GetFundingRateValueResponse, GetIndexRequest, GetIndexResponse, GetInstrumentsRequest,
-    GetInstrumentsResponse,
+    Candle, CurrencyInfo, DeliveryPrice, FundingChartLength, FundingChartPoint, FundingRate,
+    GetBookSummaryByCurrencyRequest, GetBookSummaryByCurrencyResponse,
+    GetBookSummaryByInstrumentRequest, GetBookSummaryByInstrumentResponse, GetCurrenciesRequest,
+    GetCurrenciesResponse, GetDeliveryPricesRequest, GetDeliveryPricesResponse,
+    GetFundingChartDataRequest, GetFundingChartDataResponse, GetFundingRateHistoryRequest,
+    GetFundingRateHistoryResponse, GetFundingRateValueRequest, GetFundingRateValueResponse,
+    GetHistoricalVolatilityRequest, GetHistoricalVolatilityResponse, GetIndexRequest,
+    GetIndexResponse, GetInstrumentsRequest, GetInstrumentsResponse,
+    GetLastSettlementsByCurrencyRequest, GetLastSettlementsByCurrencyResponse,
+    GetLastSettlementsByInstrumentRequest, GetLastSettlementsByInstrumentResponse,
+    GetLastTradesByCurrencyAndTimeRequest, GetLastTradesByCurrencyAndTimeResponse,
+    GetLastTradesByCurrencyRequest, GetLastTradesByCurrencyResponse,
+    GetLastTradesByInstrumentAndTimeRequest, GetLastTradesByInstrumentAndTimeResponse,
+    GetLastTradesByInstrumentRequest, GetLastTradesByInstrumentResponse, GetLastTradesResponse,
+    GetOrderBookRequest, GetOrderBookResponse, GetTradeVolumesRequest, GetTradeVolumesResponse,
+    GetTradingviewChartDataRequest, GetTradingviewChartDataResponse, Resolution, TickerRequest,
+    TickerResponse, TradeVolume, WithdrawalPriority,
+};
+pub use mass_quote::{
+    CancelQuotesRequest, CancelQuotesResponse, CancelQuotesType, MassQuoteError, MassQuoteItem,
+    MassQuoteLevel, MassQuoteRequest, MassQuoteResponse, QuoteSide,
 };
+pub use mmp::{
+    GetMmpConfigRequest, GetMmpConfigResponse, MmpConfig, ResetMmpRequest, ResetMmpResponse,
+    SetMmpConfigRequest, SetMmpConfigResponse,
+};
+pub use order_builder::OrderBuilder;
 pub use session_management::{
     CancelOnDisconnectScope, DisableCancelOnDisconnectRequest, DisableCancelOnDisconnectResponse,
     EnableCancelOnDisconnectRequest, GetCancelOnDisconnectRequest, GetCancelOnDisconnectResponse,
@@ -42,15 +102,36 @@ pub use support::{
 pub use trading::{
     BuyRequest, BuyResponse, CancelAllByCurrencyRequest, CancelAllByInstrumentRequest,
     CancelAllRequest, CancelAllResponse, CancelByLabelRequest, CancelOrderType, CancelRequest,
-    CancelResponse, EditRequest, EditResponse, GetOpenOrderType, GetOpenOrdersByCurrencyRequest,
-    GetOpenOrdersByCurrencyResponse, GetOpenOrdersByInstrumentRequest,
-    GetOpenOrdersByInstrumentResponse, GetOrderStateRequest, GetOrderStateResponse, Order,
-    SellRequest, SellResponse, Trade, TradeRequest, TradeResponse,
+    CancelResponse, ClosePositionRequest, ClosePositionResponse, ClosePositionType,
+    EditByLabelRequest, EditRequest, EditResponse, GetMarginsRequest, GetMarginsResponse,
+    GetOpenOrderType, GetOpenOrdersByCurrencyRequest, GetOpenOrdersByCurrencyResponse,
+    GetOpenOrdersByInstrumentRequest, GetOpenOrdersByInstrumentResponse,
+    GetOpenOrdersByLabelRequest, GetOpenOrdersByLabelResponse, GetOrderHistoryByCurrencyRequest,
+    GetOrderHistoryByCurrencyResponse, GetOrderHistoryByInstrumentRequest,
+    GetOrderHistoryByInstrumentResponse, GetOrderMarginByIdsRequest, GetOrderMarginByIdsResponse,
+    GetOrderStateRequest, GetOrderStateResponse, GetSettlementHistoryByCurrencyRequest,
+    GetSettlementHistoryByCurrencyResponse, GetSettlementHistoryByInstrumentRequest,
+    GetSettlementHistoryByInstrumentResponse, GetSettlementHistoryResponse,
+    GetStopOrderHistoryRequest, GetStopOrderHistoryResponse, GetUserTradesByCurrencyAndTimeRequest,
+    GetUserTradesByCurrencyAndTimeResponse, GetUserTradesByCurrencyRequest,
+    GetUserTradesByCurrencyResponse, GetUserTradesByInstrumentAndTimeRequest,
+    GetUserTradesByInstrumentAndTimeResponse, GetUserTradesByInstrumentRequest,
+    GetUserTradesByInstrumentResponse, GetUserTradesByOrderRequest, GetUserTradesByOrderResponse,
+    GetUserTradesResponse, Order, OrderMargin, OtocoOrder, SellRequest, SellResponse, Settlement,
+    SettlementType, StopOrderHistoryEntry, Trade, TradeRequest, TradeResponse,
 };
 pub use wallet::{
-    GetTransfersRequest, GetTransfersResponse, SubmitTransferToSubaccountRequest,
-    SubmitTransferToSubaccountResponse, SubmitTransferToUserRequest, SubmitTransferToUserResponse,
-    TransferItem, WithdrawRequest, WithdrawResponse,
+    AddToAddressBookRequest, AddToAddressBookResponse, AddressBookEntry, AddressBookType,
+    CancelTransferByIdRequest, CancelTransferByIdResponse, CancelWithdrawalRequest,
+    CancelWithdrawalResponse, CreateDepositAddressRequest, CreateDepositAddressResponse, Deposit,
+    DepositAddress, GetAddressBookRequest, GetAddressBookResponse, GetCurrentDepositAddressRequest,
+    GetCurrentDepositAddressResponse, GetDepositsRequest, GetDepositsResponse, GetTransfersRequest,
+    GetTransfersResponse, GetWithdrawalsRequest, GetWithdrawalsResponse,
+    RemoveFromAddressBookRequest, RemoveFromAddressBookResponse,
+    SubmitTransferBetweenSubaccountsRequest, SubmitTransferBetweenSubaccountsResponse,
+    SubmitTransferToSubaccountRequest, SubmitTransferToSubaccountResponse,
+    SubmitTransferToUserRequest, SubmitTransferToUserResponse, TransferItem, WithdrawRequest,
+    WithdrawResponse, Withdrawal,
 };
 
 pub trait Request {
@@ -63,16 +144,73 @@ pub trait Request {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// A request whose results span several pages, see `DeribitAPIClient::call_paginated`.
+pub trait PaginatedRequest: Request + Clone {
+    type Item;
+
+    /// Splits a page into its items and the request fetching the next page, if there is one.
+    fn paginate(&self, response: Self::Response) -> crate::Result<(Vec<Self::Item>, Option<Self>)>;
+}
+
+/// The page size of the offset paginated listings when the request sets no `count`
+const DEFAULT_PAGE_SIZE: u64 = 20;
+
+/// The offset of the next page of an offset paginated listing, if there is one. Without
+/// the `total` number of items, a full page of `count` items means there might be more.
+pub(crate) fn next_offset(
+    count: Option<u64>,
+    offset: Option<u64>,
+    len: usize,
+    total: Option<u64>,
+) -> Option<u64> {
+    let next = offset.unwrap_or(0) + len as u64;
+    let more = match total {
+        Some(total) => next < total,
+        None => len as u64 >= count.unwrap_or(DEFAULT_PAGE_SIZE),
+    };
+    Some(next).filter(|_| len > 0 && more)
+}
+
+/// A request over a time range wider than the API serves at once,
+/// see `DeribitAPIClient::call_chunked`.
+pub trait ChunkedRequest: Request + Clone {
+    type Item;
+
+    /// The widest range a single request may span.
+    fn max_window(&self) -> chrono::Duration;
+
+    /// The inclusive `(start, end)` range in milliseconds.
+    fn range(&self) -> (u64, u64);
+
+    fn with_range(&self, start: u64, end: u64) -> Self;
+
+    /// The items of a response, in ascending timestamp order.
+    fn items(response: Self::Response) -> crate::Result<Vec<Self::Item>>;
+
+    fn timestamp(item: &Self::Item) -> u64;
+
+    /// Drops the items already yielded by the previous window, where the windows meet.
+    /// `last` is the latest timestamp yielded so far, returned updated with the items.
+    fn dedup(items: Vec<Self::Item>, last: Option<u64>) -> (Vec<Self::Item>, Option<u64>) {
+        let items: Vec<_> = items
+            .into_iter()
+            .filter(|item| last.map_or(true, |last| Self::timestamp(item) > last))
+            .collect();
+        let last = items.iter().map(Self::timestamp).max().or(last);
+        (items, last)
+    }
+}
+
+/// A Deribit currency code. Codes unknown to this crate are kept as `Other`,
+/// see `CurrencyRegistry` for their metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Currency {
-    #[serde(alias = "btc")]
     BTC,
-    #[serde(alias = "eth")]
     ETH,
-    #[serde(alias = "usd")]
     USD,
-    #[serde(alias = "usdt")]
+    USDC,
     USDT,
+    Other(String),
 }
 
 impl Default for Currency {
@@ -81,9 +219,22 @@ impl Default for Currency {
     }
 }
 
+impl Currency {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Currency::BTC => "BTC",
+            Currency::ETH => "ETH",
+            Currency::USD => "USD",
+            Currency::USDC => "USDC",
+            Currency::USDT => "USDT",
+            Currency::Other(code) => code,
+        }
+    }
+}
+
 impl std::fmt::Display for Currency {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(fmt, "{:?}", self)
+        fmt.write_str(self.as_str())
     }
 }
 
@@ -91,8 +242,28 @@ impl std::str::FromStr for Currency {
     type Err = DeribitError;
     #[throws(DeribitError)]
     fn from_str(s: &str) -> Currency {
-        from_str(&format!(r#""{}""#, s))
-            .map_err(|_| DeribitError::UnknownCurrency(s.to_string()))?
+        match s.to_uppercase().as_str() {
+            "" => throw!(DeribitError::UnknownCurrency(s.to_string())),
+            "BTC" => Currency::BTC,
+            "ETH" => Currency::ETH,
+            "USD" => Currency::USD,
+            "USDC" => Currency::USDC,
+            "USDT" => Currency::USDT,
+            code => Currency::Other(code.to_string()),
+        }
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
     }
 }
 
@@ -163,6 +334,10 @@ pub enum OrderType {
     Market,
     StopLimit,
     StopMarket,
+    TakeLimit,
+    TakeMarket,
+    MarketLimit,
+    TrailingStop,
     Liquidation,
 }
 
@@ -206,6 +381,32 @@ pub enum Trigger {
     LastPrice,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkedOrderType {
+    OneTriggersOther,
+    OneCancelsOther,
+    OneTriggersOneCancelsOther,
+}
+
+/// When the secondary orders of an OTO/OTOCO order are placed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerFillCondition {
+    FirstHit,
+    CompleteFill,
+    Incremental,
+}
+
+/// Pagination follows the sorting direction, walking backwards in time for `Desc`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Sorting {
+    Asc,
+    Desc,
+    Default,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub enum AdvanceOption {
     #[serde(rename = "usd")]
@@ -228,6 +429,13 @@ pub enum TransferState {
     RejectionReason,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    Payment,
+    Income,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub enum TransferType {
     #[serde(rename = "user")]
@@ -236,7 +444,7 @@ pub enum TransferType {
     Subaccount,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub enum Priority {
     #[serde(rename = "insane")]
     Insane,
@@ -254,12 +462,12 @@ pub enum Priority {
     VeryLow,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub enum WithdrawState {
     #[serde(rename = "unconfirmed")]
     Unconfirmed,
     #[serde(rename = "confirmed")]
-    Priority,
+    Confirmed,
     #[serde(rename = "cancelled")]
     Cancelled,
     #[serde(rename = "completed")]
@@ -270,6 +478,15 @@ pub enum WithdrawState {
     Rejected,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositState {
+    Pending,
+    Completed,
+    Rejected,
+    Replaced,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Either<L, R> {
@@ -0,0 +1,229 @@
+//! A typed builder for `BuyRequest` and `SellRequest`.
+//!
+//! The builder tracks the side, the order kind and whether the amount is set in its type,
+//! so that e.g. `post_only` on a market order or a request without amount do not compile:
+//!
+//! ```
+//! use deribit::models::Order;
+//!
+//! let req = Order::buy("BTC-PERPETUAL")
+//!     .limit(10000.)
+//!     .amount(10.)
+//!     .post_only()
+//!     .label("x")
+//!     .build();
+//! ```
+//!
+//! ```compile_fail
+//! use deribit::models::Order;
+//!
+//! let req = Order::sell("BTC-PERPETUAL").market().amount(10.).post_only().build();
+//! ```
+use crate::models::{
+    BuyRequest, Order, OrderType, SellRequest, TimeInForce, TradeRequest, Trigger,
+};
+use chrono::{DateTime, Utc};
+use std::marker::PhantomData;
+
+pub struct Buy;
+pub struct Sell;
+
+/// The order kind is not chosen yet.
+pub struct Unset;
+pub struct Market;
+pub struct Limit;
+pub struct StopMarket;
+pub struct StopLimit;
+pub struct TakeMarket;
+pub struct TakeLimit;
+pub struct TrailingStop;
+
+pub struct NoAmount;
+pub struct WithAmount;
+
+pub trait Side {
+    type Request;
+    fn wrap(request: TradeRequest) -> Self::Request;
+}
+
+impl Side for Buy {
+    type Request = BuyRequest;
+    fn wrap(request: TradeRequest) -> BuyRequest {
+        BuyRequest(request)
+    }
+}
+
+impl Side for Sell {
+    type Request = SellRequest;
+    fn wrap(request: TradeRequest) -> SellRequest {
+        SellRequest(request)
+    }
+}
+
+/// Any order kind but `Unset`.
+pub trait Kind {}
+impl Kind for Market {}
+impl Kind for Limit {}
+impl Kind for StopMarket {}
+impl Kind for StopLimit {}
+impl Kind for TakeMarket {}
+impl Kind for TakeLimit {}
+impl Kind for TrailingStop {}
+
+/// Kinds resting in the book as soon as they are placed.
+pub trait Resting {}
+impl Resting for Limit {}
+
+/// Kinds only placed once their trigger is hit.
+pub trait Triggered {}
+impl Triggered for StopMarket {}
+impl Triggered for StopLimit {}
+impl Triggered for TakeMarket {}
+impl Triggered for TakeLimit {}
+impl Triggered for TrailingStop {}
+
+pub struct OrderBuilder<S, K, A> {
+    request: TradeRequest,
+    _state: PhantomData<(S, K, A)>,
+}
+
+impl Order {
+    pub fn buy<I: Into<String>>(instrument_name: I) -> OrderBuilder<Buy, Unset, NoAmount> {
+        OrderBuilder::new(instrument_name.into())
+    }
+
+    pub fn sell<I: Into<String>>(instrument_name: I) -> OrderBuilder<Sell, Unset, NoAmount> {
+        OrderBuilder::new(instrument_name.into())
+    }
+}
+
+impl<S, K, A> OrderBuilder<S, K, A> {
+    fn into_state<K2, A2>(self) -> OrderBuilder<S, K2, A2> {
+        OrderBuilder {
+            request: self.request,
+            _state: PhantomData,
+        }
+    }
+
+    pub fn label<L: Into<String>>(mut self, label: L) -> Self {
+        self.request.label = Some(label.into());
+        self
+    }
+
+    pub fn reduce_only(mut self) -> Self {
+        self.request.reduce_only = true;
+        self
+    }
+
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.request.time_in_force = time_in_force;
+        self
+    }
+
+    pub fn valid_until(mut self, deadline: DateTime<Utc>) -> Self {
+        self.request.valid_until = Some(deadline.timestamp_millis() as u64);
+        self
+    }
+}
+
+impl<S, A> OrderBuilder<S, Unset, A> {
+    fn kind<K>(mut self, r#type: OrderType) -> OrderBuilder<S, K, A> {
+        self.request.r#type = r#type;
+        self.into_state()
+    }
+
+    fn triggered<K>(mut self, r#type: OrderType, stop_price: f64) -> OrderBuilder<S, K, A> {
+        self.request.stop_price = Some(stop_price);
+        self.request.trigger = Some(Trigger::LastPrice);
+        self.kind(r#type)
+    }
+
+    pub fn market(self) -> OrderBuilder<S, Market, A> {
+        self.kind(OrderType::Market)
+    }
+
+    pub fn limit(mut self, price: f64) -> OrderBuilder<S, Limit, A> {
+        self.request.price = Some(price);
+        self.kind(OrderType::Limit)
+    }
+
+    pub fn stop_market(self, stop_price: f64) -> OrderBuilder<S, StopMarket, A> {
+        self.triggered(OrderType::StopMarket, stop_price)
+    }
+
+    pub fn stop_limit(mut self, stop_price: f64, price: f64) -> OrderBuilder<S, StopLimit, A> {
+        self.request.price = Some(price);
+        self.triggered(OrderType::StopLimit, stop_price)
+    }
+
+    pub fn take_market(self, stop_price: f64) -> OrderBuilder<S, TakeMarket, A> {
+        self.triggered(OrderType::TakeMarket, stop_price)
+    }
+
+    pub fn take_limit(mut self, stop_price: f64, price: f64) -> OrderBuilder<S, TakeLimit, A> {
+        self.request.price = Some(price);
+        self.triggered(OrderType::TakeLimit, stop_price)
+    }
+
+    pub fn trailing_stop(mut self, trigger_offset: f64) -> OrderBuilder<S, TrailingStop, A> {
+        self.request.trigger_offset = Some(trigger_offset);
+        self.request.trigger = Some(Trigger::LastPrice);
+        self.kind(OrderType::TrailingStop)
+    }
+}
+
+impl<S, K> OrderBuilder<S, K, NoAmount> {
+    pub fn amount(mut self, amount: f64) -> OrderBuilder<S, K, WithAmount> {
+        self.request.amount = amount;
+        self.into_state()
+    }
+}
+
+impl<S, K: Resting, A> OrderBuilder<S, K, A> {
+    pub fn post_only(mut self) -> Self {
+        self.request.post_only = true;
+        self
+    }
+
+    /// Rejects the order instead of repricing it when it would take liquidity.
+    pub fn reject_post_only(mut self) -> Self {
+        self.request.post_only = true;
+        self.request.reject_post_only = Some(true);
+        self
+    }
+
+    pub fn mmp(mut self) -> Self {
+        self.request.mmp = Some(true);
+        self
+    }
+
+    pub fn display_amount(mut self, display_amount: f64) -> Self {
+        self.request.display_amount = Some(display_amount);
+        self
+    }
+}
+
+impl<S, K: Triggered, A> OrderBuilder<S, K, A> {
+    pub fn trigger(mut self, trigger: Trigger) -> Self {
+        self.request.trigger = Some(trigger);
+        self
+    }
+}
+
+impl<S: Side, K: Kind> OrderBuilder<S, K, WithAmount> {
+    pub fn build(self) -> S::Request {
+        S::wrap(self.request)
+    }
+}
+
+impl<S> OrderBuilder<S, Unset, NoAmount> {
+    fn new(instrument_name: String) -> Self {
+        OrderBuilder {
+            request: TradeRequest {
+                instrument_name,
+                ..Default::default()
+            },
+            _state: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::models::subscription::TradesData;
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BlockTradeData {
+    pub id: String,
+    pub timestamp: u64,
+    pub trades: Vec<TradesData>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockTradeChannel(String);
+impl<'de> Deserialize<'de> for BlockTradeChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["block_trade", currency] => Ok(BlockTradeChannel(currency.to_string())),
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"block_trade.{currency}"
+            )),
+        }
+    }
+}
+impl Serialize for BlockTradeChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("block_trade.{}", self.0))
+    }
+}
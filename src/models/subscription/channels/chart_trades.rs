@@ -0,0 +1,47 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The candle of the current period, sent again on every trade updating it.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChartTradesData {
+    pub tick: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartTradesChannel(String, String);
+impl<'de> Deserialize<'de> for ChartTradesChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["chart", "trades", instrument_name, resolution] => Ok(ChartTradesChannel(
+                instrument_name.to_string(),
+                resolution.to_string(),
+            )),
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"chart.trades.{instrument_name}.{resolution}"
+            )),
+        }
+    }
+}
+impl Serialize for ChartTradesChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("chart.trades.{}.{}", self.0, self.1))
+    }
+}
@@ -0,0 +1,99 @@
+use crate::models::subscription::{Greeks, Stats};
+use crate::models::OrderState;
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncrementalTickerType {
+    Snapshot,
+    Change,
+}
+
+/// The greeks of a `Change`, only carrying the ones that changed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct GreeksUpdate {
+    pub delta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub rho: Option<f64>,
+    pub theta: Option<f64>,
+    pub vega: Option<f64>,
+}
+
+impl GreeksUpdate {
+    /// The greeks, when all of them are present.
+    pub fn complete(&self) -> Option<Greeks> {
+        Some(Greeks {
+            delta: self.delta?,
+            gamma: self.gamma?,
+            rho: self.rho?,
+            theta: self.theta?,
+            vega: self.vega?,
+        })
+    }
+}
+
+/// The first notification is a full `Snapshot` of the ticker, the following `Change`s
+/// only carry the fields that changed since the previous notification.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct IncrementalTickerData {
+    #[serde(rename = "type")]
+    pub ty: IncrementalTickerType,
+    pub instrument_name: String,
+    pub timestamp: u64,
+    pub ask_iv: Option<f64>,
+    pub best_ask_amount: Option<f64>,
+    pub best_ask_price: Option<f64>,
+    pub best_bid_amount: Option<f64>,
+    pub best_bid_price: Option<f64>,
+    pub bid_iv: Option<f64>,
+    pub current_funding: Option<f64>,
+    pub delivery_price: Option<f64>,
+    pub funding_8h: Option<f64>,
+    pub greeks: Option<GreeksUpdate>,
+    pub index_price: Option<f64>,
+    pub interest_rate: Option<f64>,
+    pub last_price: Option<f64>,
+    pub mark_iv: Option<f64>,
+    pub mark_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub min_price: Option<f64>,
+    pub open_interest: Option<f64>,
+    pub settlement_price: Option<f64>,
+    pub state: Option<OrderState>,
+    pub stats: Option<Stats>,
+    pub underlying_index: Option<String>,
+    pub underlying_price: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IncrementalTickerChannel(String);
+impl<'de> Deserialize<'de> for IncrementalTickerChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["incremental_ticker", instrument_name] => {
+                Ok(IncrementalTickerChannel(instrument_name.to_string()))
+            }
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"incremental_ticker.{instrument_name}"
+            )),
+        }
+    }
+}
+impl Serialize for IncrementalTickerChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("incremental_ticker.{}", self.0))
+    }
+}
@@ -11,6 +11,7 @@ pub enum InstrumentState {
     Started,
     Settled,
     Closed,
+    Deactivated,
     Terminated,
 }
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -21,6 +22,7 @@ pub struct InstrumentStateData {
     pub instrument_name: String,
 }
 
+/// `kind` and `currency` can both be `any`
 #[derive(Debug, Clone)]
 pub struct InstrumentStateChannel(String, String);
 impl<'de> Deserialize<'de> for InstrumentStateChannel {
@@ -1,31 +1,53 @@
 mod announcements;
+mod block_trade;
 mod book;
+mod chart_trades;
 mod deribit_price_index;
 mod deribit_price_ranking;
+mod deribit_volatility_index;
 mod estimated_expiration_price;
+mod incremental_ticker;
 mod instrument;
 mod markprice;
 mod perpetual;
+mod platform_state;
+mod price_index;
 mod quote;
 mod ticker;
 mod trades;
+mod user_access_log;
+mod user_changes;
+mod user_lock;
+mod user_mmp_trigger;
 mod user_orders;
 mod user_portfolio;
 mod user_trades;
 
 pub use announcements::{AnnouncementsChannel, AnnouncementsData};
+pub use block_trade::{BlockTradeChannel, BlockTradeData};
 pub use book::{BookChannel, BookData, Delta, GroupedBookChannel, GroupedBookData, OrderBookDelta};
+pub use chart_trades::{ChartTradesChannel, ChartTradesData};
 pub use deribit_price_index::{DeribitPriceIndexChannel, DeribitPriceIndexData};
 pub use deribit_price_ranking::{DeribitPriceRankingChannel, DeribitPriceRankingData};
+pub use deribit_volatility_index::{DeribitVolatilityIndexChannel, DeribitVolatilityIndexData};
 pub use estimated_expiration_price::{
     EstimatedExpirationPriceChannel, EstimatedExpirationPriceData,
 };
+pub use incremental_ticker::{
+    GreeksUpdate, IncrementalTickerChannel, IncrementalTickerData, IncrementalTickerType,
+};
 pub use instrument::{InstrumentState, InstrumentStateChannel, InstrumentStateData};
 pub use markprice::{MarkPriceOptionChannel, MarkPriceOptionData};
 pub use perpetual::{PerpetualChannel, PerpetualData};
+pub use platform_state::{PlatformStateChannel, PlatformStateData};
+pub use price_index::{PriceIndexChannel, PriceIndexData};
 pub use quote::{QuoteChannel, QuoteData};
 pub use ticker::{Greeks, Stats, TickerChannel, TickerData};
 pub use trades::{TradesChannel, TradesData};
+pub use user_access_log::{UserAccessLogChannel, UserAccessLogData};
+pub use user_changes::{UserChangesChannel, UserChangesData};
+pub use user_lock::{UserLockChannel, UserLockData};
+pub use user_mmp_trigger::{UserMmpTriggerChannel, UserMmpTriggerData};
 pub use user_orders::{UserOrdersChannel, UserOrdersData};
 pub use user_portfolio::{UserPortfolioChannel, UserPortfolioData};
 pub use user_trades::{UserTradesChannel, UserTradesData};
@@ -0,0 +1,49 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// A change of the platform state, e.g. `{"price_index": "btc_usd", "locked": true}`
+/// when trading on the instruments of an index is suspended.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PlatformStateData {
+    pub price_index: Option<String>,
+    pub locked: Option<bool>,
+    pub maintenance: Option<bool>,
+    pub allow_unauthenticated_public_requests: Option<bool>,
+}
+
+impl PlatformStateData {
+    /// The price index and whether it got locked, for lock and unlock events.
+    pub fn index_lock(&self) -> Option<(&str, bool)> {
+        Some((self.price_index.as_deref()?, self.locked?))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PlatformStateChannel;
+impl<'de> Deserialize<'de> for PlatformStateChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        if s == "platform_state" {
+            Ok(PlatformStateChannel)
+        } else {
+            throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"platform_state"
+            ))
+        }
+    }
+}
+impl Serialize for PlatformStateChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str("platform_state")
+    }
+}
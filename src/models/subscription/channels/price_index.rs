@@ -0,0 +1,39 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PriceIndexData {
+    pub index_name: String,
+    pub price: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceIndexChannel(String);
+impl<'de> Deserialize<'de> for PriceIndexChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["price_index", index_name] => Ok(PriceIndexChannel(index_name.to_string())),
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"price_index.{index_name}"
+            )),
+        }
+    }
+}
+impl Serialize for PriceIndexChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("price_index.{}", self.0))
+    }
+}
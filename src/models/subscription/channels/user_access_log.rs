@@ -0,0 +1,43 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct UserAccessLogData {
+    pub id: u64,
+    pub timestamp: u64,
+    /// `success` or `failure` of the login
+    pub log: String,
+    pub ip: String,
+    pub country: Option<String>,
+    pub city: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UserAccessLogChannel;
+impl<'de> Deserialize<'de> for UserAccessLogChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        if s == "user.access_log" {
+            Ok(UserAccessLogChannel)
+        } else {
+            throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"user.access_log"
+            ))
+        }
+    }
+}
+impl Serialize for UserAccessLogChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str("user.access_log")
+    }
+}
@@ -0,0 +1,79 @@
+use crate::models::subscription::{UserOrdersData, UserTradesData};
+use crate::models::GetPositionsResponse;
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The orders, trades and positions changed by one event, delivered together so that
+/// the three of them can be updated consistently.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct UserChangesData {
+    pub instrument_name: String,
+    #[serde(default)]
+    pub orders: Vec<UserOrdersData>,
+    #[serde(default)]
+    pub trades: Vec<UserTradesData>,
+    #[serde(default)]
+    pub positions: Vec<GetPositionsResponse>,
+}
+
+#[derive(Debug, Clone)]
+pub enum UserChangesChannel {
+    ByInstrument {
+        instrument_name: String,
+        interval: String,
+    },
+    ByKind {
+        kind: String,
+        currency: String,
+        interval: String,
+    },
+}
+
+impl<'de> Deserialize<'de> for UserChangesChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["user", "changes", instrument_name, interval] => Ok(UserChangesChannel::ByInstrument {
+                instrument_name: instrument_name.to_string(),
+                interval: interval.to_string(),
+            }),
+            ["user", "changes", kind, currency, interval] => Ok(UserChangesChannel::ByKind {
+                kind: kind.to_string(),
+                currency: currency.to_string(),
+                interval: interval.to_string(),
+            }),
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"user.changes.{instrument_name}.{interval} or user.changes.{kind}.{currency}.{interval}"
+            )),
+        }
+    }
+}
+impl Serialize for UserChangesChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            UserChangesChannel::ByInstrument {
+                instrument_name,
+                interval,
+            } => {
+                serializer.serialize_str(&format!("user.changes.{}.{}", instrument_name, interval))
+            }
+            UserChangesChannel::ByKind {
+                kind,
+                currency,
+                interval,
+            } => serializer
+                .serialize_str(&format!("user.changes.{}.{}.{}", kind, currency, interval)),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct UserLockData {
+    /// The locked currency, or `ALL` when the whole account is locked
+    pub currency: String,
+    pub locked: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UserLockChannel;
+impl<'de> Deserialize<'de> for UserLockChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        if s == "user.lock" {
+            Ok(UserLockChannel)
+        } else {
+            throw!(D::Error::invalid_value(Unexpected::Str(s), &"user.lock"))
+        }
+    }
+}
+impl Serialize for UserLockChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str("user.lock")
+    }
+}
@@ -0,0 +1,40 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct UserMmpTriggerData {
+    pub index_name: Option<String>,
+    pub mmp_group: Option<String>,
+    /// Timestamp until which MMP orders are frozen, 0 if they stay frozen until reset
+    pub frozen_until: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserMmpTriggerChannel(String);
+impl<'de> Deserialize<'de> for UserMmpTriggerChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["user", "mmp_trigger", currency] => Ok(UserMmpTriggerChannel(currency.to_string())),
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(s),
+                &"user.mmp_trigger.{currency}"
+            )),
+        }
+    }
+}
+impl Serialize for UserMmpTriggerChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("user.mmp_trigger.{}", self.0))
+    }
+}
@@ -35,6 +35,16 @@ pub struct UserOrdersData {
     pub usd: Option<f64>,
     pub replaced: bool, // TODO: Remove the Option when necessary
     pub web: bool,
+    pub trigger_offset: Option<f64>,
+    pub trigger_price: Option<f64>,
+    pub reject_post_only: Option<bool>,
+    pub mmp: Option<bool>,
+    pub mmp_cancelled: Option<bool>,
+    pub display_amount: Option<f64>,
+    pub oto_order_ids: Option<Vec<String>>,
+    pub oco_ref: Option<String>,
+    pub primary_order_id: Option<String>,
+    pub is_secondary_oto: Option<bool>,
 }
 
 #[derive(Debug, Clone)]
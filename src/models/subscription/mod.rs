@@ -3,18 +3,30 @@ mod channels;
 use crate::models::jsonrpc::JSONRPCVersion;
 use crate::models::Request;
 pub use channels::{AnnouncementsChannel, AnnouncementsData};
+pub use channels::{BlockTradeChannel, BlockTradeData};
 pub use channels::{
     BookChannel, BookData, Delta, GroupedBookChannel, GroupedBookData, OrderBookDelta,
 };
+pub use channels::{ChartTradesChannel, ChartTradesData};
 pub use channels::{DeribitPriceIndexChannel, DeribitPriceIndexData};
 pub use channels::{DeribitPriceRankingChannel, DeribitPriceRankingData};
+pub use channels::{DeribitVolatilityIndexChannel, DeribitVolatilityIndexData};
 pub use channels::{EstimatedExpirationPriceChannel, EstimatedExpirationPriceData};
 pub use channels::{Greeks, Stats, TickerChannel, TickerData};
+pub use channels::{
+    GreeksUpdate, IncrementalTickerChannel, IncrementalTickerData, IncrementalTickerType,
+};
 pub use channels::{InstrumentState, InstrumentStateChannel, InstrumentStateData};
 pub use channels::{MarkPriceOptionChannel, MarkPriceOptionData};
 pub use channels::{PerpetualChannel, PerpetualData};
+pub use channels::{PlatformStateChannel, PlatformStateData};
+pub use channels::{PriceIndexChannel, PriceIndexData};
 pub use channels::{QuoteChannel, QuoteData};
 pub use channels::{TradesChannel, TradesData};
+pub use channels::{UserAccessLogChannel, UserAccessLogData};
+pub use channels::{UserChangesChannel, UserChangesData};
+pub use channels::{UserLockChannel, UserLockData};
+pub use channels::{UserMmpTriggerChannel, UserMmpTriggerData};
 pub use channels::{UserOrdersChannel, UserOrdersData};
 pub use channels::{UserPortfolioChannel, UserPortfolioData};
 pub use channels::{UserTradesChannel, UserTradesData};
@@ -177,19 +189,29 @@ pub struct WithChannel<C, D> {
 #[serde(untagged)]
 pub enum SubscriptionData {
     Announcements(WithChannel<AnnouncementsChannel, AnnouncementsData>),
+    BlockTrade(WithChannel<BlockTradeChannel, BlockTradeData>),
     Book(WithChannel<BookChannel, BookData>),
+    ChartTrades(WithChannel<ChartTradesChannel, ChartTradesData>),
     DeribitPriceIndex(WithChannel<DeribitPriceIndexChannel, DeribitPriceIndexData>),
     DeribitPriceRanking(WithChannel<DeribitPriceRankingChannel, Vec<DeribitPriceRankingData>>),
+    DeribitVolatilityIndex(WithChannel<DeribitVolatilityIndexChannel, DeribitVolatilityIndexData>),
     EstimatedExpirationPrice(
         WithChannel<EstimatedExpirationPriceChannel, EstimatedExpirationPriceData>,
     ),
     GroupedBook(WithChannel<GroupedBookChannel, GroupedBookData>),
+    IncrementalTicker(WithChannel<IncrementalTickerChannel, IncrementalTickerData>),
     InstrumentState(WithChannel<InstrumentStateChannel, InstrumentStateData>),
     MarkPriceOption(WithChannel<MarkPriceOptionChannel, Vec<MarkPriceOptionData>>),
     Perpetual(WithChannel<PerpetualChannel, PerpetualData>),
+    PlatformState(WithChannel<PlatformStateChannel, PlatformStateData>),
+    PriceIndex(WithChannel<PriceIndexChannel, PriceIndexData>),
     Quote(WithChannel<QuoteChannel, QuoteData>),
     Ticker(WithChannel<TickerChannel, TickerData>),
     Trades(WithChannel<TradesChannel, Vec<TradesData>>), // This should be put after user trades otherwise all usertrades will be deserialized to trades
+    UserAccessLog(WithChannel<UserAccessLogChannel, UserAccessLogData>),
+    UserChanges(WithChannel<UserChangesChannel, UserChangesData>),
+    UserLock(WithChannel<UserLockChannel, UserLockData>),
+    UserMmpTrigger(WithChannel<UserMmpTriggerChannel, UserMmpTriggerData>),
     UserOrders(WithChannel<UserOrdersChannel, UserOrdersData>),
     UserOrdersBatch(WithChannel<UserOrdersChannel, Vec<UserOrdersData>>),
     UserPortfolio(WithChannel<UserPortfolioChannel, UserPortfolioData>),
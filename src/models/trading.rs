@@ -1,10 +1,16 @@
+use crate::errors::DeribitError;
 use crate::models::{
-    AdvanceOption, AssetKind, Currency, Direction, Either, LiquidityType, OrderState, OrderType,
-    Request, TimeInForce, Trigger,
+    next_offset, AdvanceOption, AssetKind, Currency, Direction, Either, LinkedOrderType,
+    LiquidityType, OrderState, OrderType, PaginatedRequest, Request, Sorting, TimeInForce, Trigger,
+    TriggerFillCondition,
 };
+use chrono::{DateTime, Utc};
+use failure::Error;
+use fehler::{throw, throws};
 use serde::Deserializer;
 use serde::{Deserialize, Serialize};
 use shrinkwraprs::Shrinkwrap;
+use std::convert::TryFrom;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Shrinkwrap)]
 #[shrinkwrap(mutable)]
@@ -19,13 +25,19 @@ impl BuyRequest {
     where
         I: Into<String>,
     {
-        BuyRequest(TradeRequest::market(instrument_name, amount))
+        Order::buy(instrument_name).market().amount(amount).build()
     }
     pub fn limit<I>(instrument_name: I, price: f64, amount: f64) -> BuyRequest
     where
         I: Into<String>,
     {
-        BuyRequest(TradeRequest::limit(instrument_name, price, amount))
+        Order::buy(instrument_name)
+            .limit(price)
+            .amount(amount)
+            .build()
+    }
+    pub fn with_label<S: Into<String>>(self, label: S) -> BuyRequest {
+        BuyRequest(self.0.with_label(label))
     }
 }
 
@@ -47,13 +59,19 @@ impl SellRequest {
     where
         I: Into<String>,
     {
-        SellRequest(TradeRequest::market(instrument_name, amount))
+        Order::sell(instrument_name).market().amount(amount).build()
     }
     pub fn limit<I>(instrument_name: I, price: f64, amount: f64) -> SellRequest
     where
         I: Into<String>,
     {
-        SellRequest(TradeRequest::limit(instrument_name, price, amount))
+        Order::sell(instrument_name)
+            .limit(price)
+            .amount(amount)
+            .build()
+    }
+    pub fn with_label<S: Into<String>>(self, label: S) -> SellRequest {
+        SellRequest(self.0.with_label(label))
     }
 }
 
@@ -85,8 +103,8 @@ impl EditRequest {
     pub fn new(order_id: &str, price: f64, amount: f64) -> Self {
         Self {
             order_id: order_id.to_string(),
-            amount: amount,
-            price: price,
+            amount,
+            price,
             post_only: None,
             reduce_only: None,
             reject_post_only: None,
@@ -106,6 +124,46 @@ impl Request for EditRequest {
     type Response = EditResponse;
 }
 
+/// Edits the single open order carrying `label` on `instrument_name`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct EditByLabelRequest {
+    pub label: String,
+    pub instrument_name: String,
+    pub amount: f64,
+    pub price: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advanced: Option<AdvanceOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<f64>,
+}
+
+impl EditByLabelRequest {
+    pub fn new(label: &str, instrument_name: &str, price: f64, amount: f64) -> Self {
+        Self {
+            label: label.to_string(),
+            instrument_name: instrument_name.to_string(),
+            amount,
+            price,
+            post_only: None,
+            reduce_only: None,
+            reject_post_only: None,
+            advanced: None,
+            stop_price: None,
+        }
+    }
+}
+
+impl Request for EditByLabelRequest {
+    const METHOD: &'static str = "private/edit_by_label";
+    type Response = EditResponse;
+}
+
 #[derive(Deserialize, Serialize, Clone, Default, Debug)]
 pub struct TradeRequest {
     pub instrument_name: String,
@@ -126,16 +184,115 @@ pub struct TradeRequest {
     pub trigger: Option<Trigger>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub advanced: Option<AdvanceOption>,
+    /// Distance of a trailing stop trigger from the best price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_offset: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mmp: Option<bool>,
+    /// Timestamp in milliseconds after which the request is rejected by the matching engine
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linked_order_type: Option<LinkedOrderType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_fill_condition: Option<TriggerFillCondition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otoco_config: Option<Vec<OtocoOrder>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_amount: Option<f64>,
 }
 
 impl TradeRequest {
+    pub fn with_label<S: Into<String>>(self, label: S) -> TradeRequest {
+        TradeRequest {
+            label: Some(label.into()),
+            ..self
+        }
+    }
+
+    /// Rejects a post only order instead of repricing it when it would take liquidity.
+    pub fn reject_post_only(self) -> TradeRequest {
+        TradeRequest {
+            post_only: true,
+            reject_post_only: Some(true),
+            ..self
+        }
+    }
+
+    /// Flags the order as a market maker protection order.
+    pub fn with_mmp(self) -> TradeRequest {
+        TradeRequest {
+            mmp: Some(true),
+            ..self
+        }
+    }
+
+    pub fn valid_until(self, deadline: DateTime<Utc>) -> TradeRequest {
+        TradeRequest {
+            valid_until: Some(deadline.timestamp_millis() as u64),
+            ..self
+        }
+    }
+
+    /// Shows only `display_amount` of the order in the book.
+    pub fn with_display_amount(self, display_amount: f64) -> TradeRequest {
+        TradeRequest {
+            display_amount: Some(display_amount),
+            ..self
+        }
+    }
+
+    /// Places `orders` once this order is filled according to `condition`.
+    pub fn one_triggers_other(
+        self,
+        condition: TriggerFillCondition,
+        orders: Vec<OtocoOrder>,
+    ) -> TradeRequest {
+        self.linked(LinkedOrderType::OneTriggersOther, Some(condition), orders)
+    }
+
+    /// Links this order with `orders` so that the first one filled cancels the others.
+    pub fn one_cancels_other(self, orders: Vec<OtocoOrder>) -> TradeRequest {
+        self.linked(LinkedOrderType::OneCancelsOther, None, orders)
+    }
+
+    /// Places `orders`, e.g. a take profit and a stop loss, once this order is filled
+    /// according to `condition`; the first of them filled cancels the other.
+    pub fn one_triggers_one_cancels_other(
+        self,
+        condition: TriggerFillCondition,
+        orders: Vec<OtocoOrder>,
+    ) -> TradeRequest {
+        self.linked(
+            LinkedOrderType::OneTriggersOneCancelsOther,
+            Some(condition),
+            orders,
+        )
+    }
+
+    fn linked(
+        self,
+        linked_order_type: LinkedOrderType,
+        trigger_fill_condition: Option<TriggerFillCondition>,
+        orders: Vec<OtocoOrder>,
+    ) -> TradeRequest {
+        TradeRequest {
+            linked_order_type: Some(linked_order_type),
+            trigger_fill_condition,
+            otoco_config: Some(orders),
+            ..self
+        }
+    }
+
     pub fn market<I>(instrument_name: I, amount: f64) -> TradeRequest
     where
         I: Into<String>,
     {
         TradeRequest {
             instrument_name: instrument_name.into(),
-            amount: amount,
+            amount,
             r#type: OrderType::Market,
             label: None,
             price: None,
@@ -146,6 +303,7 @@ impl TradeRequest {
             stop_price: None,
             trigger: None,
             advanced: None,
+            ..Default::default()
         }
     }
 
@@ -155,7 +313,7 @@ impl TradeRequest {
     {
         TradeRequest {
             instrument_name: instrument_name.into(),
-            amount: amount,
+            amount,
             r#type: OrderType::Limit,
             label: None,
             price: Some(price),
@@ -166,6 +324,7 @@ impl TradeRequest {
             stop_price: None,
             trigger: None,
             advanced: None,
+            ..Default::default()
         }
     }
 
@@ -175,7 +334,7 @@ impl TradeRequest {
     {
         TradeRequest {
             instrument_name: instrument_name.into(),
-            amount: amount,
+            amount,
             r#type: OrderType::StopMarket,
             label: None,
             price: None,
@@ -186,6 +345,7 @@ impl TradeRequest {
             stop_price: Some(stop_price),
             trigger: Some(Trigger::LastPrice),
             advanced: None,
+            ..Default::default()
         }
     }
 
@@ -200,7 +360,7 @@ impl TradeRequest {
     {
         TradeRequest {
             instrument_name: instrument_name.into(),
-            amount: amount,
+            amount,
             r#type: OrderType::StopLimit,
             label: None,
             price: Some(price),
@@ -211,6 +371,164 @@ impl TradeRequest {
             stop_price: Some(stop_price),
             trigger: Some(Trigger::LastPrice),
             advanced: None,
+            ..Default::default()
+        }
+    }
+
+    pub fn take_market<I>(instrument_name: I, stop_price: f64, amount: f64) -> TradeRequest
+    where
+        I: Into<String>,
+    {
+        TradeRequest {
+            r#type: OrderType::TakeMarket,
+            ..TradeRequest::stop_market(instrument_name, stop_price, amount)
+        }
+    }
+
+    pub fn take_limit<I>(
+        instrument_name: I,
+        stop_price: f64,
+        price: f64,
+        amount: f64,
+    ) -> TradeRequest
+    where
+        I: Into<String>,
+    {
+        TradeRequest {
+            r#type: OrderType::TakeLimit,
+            ..TradeRequest::stop_limit(instrument_name, stop_price, price, amount)
+        }
+    }
+
+    /// A stop market order whose trigger trails the price by `trigger_offset`.
+    pub fn trailing_stop<I>(instrument_name: I, trigger_offset: f64, amount: f64) -> TradeRequest
+    where
+        I: Into<String>,
+    {
+        TradeRequest {
+            instrument_name: instrument_name.into(),
+            amount,
+            r#type: OrderType::TrailingStop,
+            time_in_force: TimeInForce::GoodTilCancelled,
+            trigger: Some(Trigger::LastPrice),
+            trigger_offset: Some(trigger_offset),
+            ..Default::default()
+        }
+    }
+}
+
+/// A secondary order of an OTO, OCO or OTOCO order.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct OtocoOrder {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    pub direction: Direction,
+    pub r#type: OrderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_offset: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Trigger>,
+}
+
+impl OtocoOrder {
+    fn new(direction: Direction, r#type: OrderType) -> OtocoOrder {
+        OtocoOrder {
+            amount: None,
+            direction,
+            r#type,
+            label: None,
+            price: None,
+            reduce_only: None,
+            time_in_force: None,
+            post_only: None,
+            trigger_price: None,
+            trigger_offset: None,
+            trigger: None,
+        }
+    }
+
+    pub fn limit(direction: Direction, price: f64) -> OtocoOrder {
+        OtocoOrder {
+            price: Some(price),
+            ..OtocoOrder::new(direction, OrderType::Limit)
+        }
+    }
+
+    /// A take profit executed at `price` once `trigger_price` is reached.
+    pub fn take_limit(direction: Direction, trigger_price: f64, price: f64) -> OtocoOrder {
+        OtocoOrder {
+            price: Some(price),
+            trigger_price: Some(trigger_price),
+            trigger: Some(Trigger::LastPrice),
+            ..OtocoOrder::new(direction, OrderType::TakeLimit)
+        }
+    }
+
+    pub fn take_market(direction: Direction, trigger_price: f64) -> OtocoOrder {
+        OtocoOrder {
+            trigger_price: Some(trigger_price),
+            trigger: Some(Trigger::LastPrice),
+            ..OtocoOrder::new(direction, OrderType::TakeMarket)
+        }
+    }
+
+    /// A stop loss executed at market once `trigger_price` is reached.
+    pub fn stop_market(direction: Direction, trigger_price: f64) -> OtocoOrder {
+        OtocoOrder {
+            trigger_price: Some(trigger_price),
+            trigger: Some(Trigger::LastPrice),
+            ..OtocoOrder::new(direction, OrderType::StopMarket)
+        }
+    }
+
+    pub fn stop_limit(direction: Direction, trigger_price: f64, price: f64) -> OtocoOrder {
+        OtocoOrder {
+            price: Some(price),
+            trigger_price: Some(trigger_price),
+            trigger: Some(Trigger::LastPrice),
+            ..OtocoOrder::new(direction, OrderType::StopLimit)
+        }
+    }
+
+    pub fn trailing_stop(direction: Direction, trigger_offset: f64) -> OtocoOrder {
+        OtocoOrder {
+            trigger_offset: Some(trigger_offset),
+            trigger: Some(Trigger::LastPrice),
+            ..OtocoOrder::new(direction, OrderType::TrailingStop)
+        }
+    }
+
+    /// Defaults to the amount of the primary order when not set.
+    pub fn with_amount(self, amount: f64) -> OtocoOrder {
+        OtocoOrder {
+            amount: Some(amount),
+            ..self
+        }
+    }
+
+    pub fn with_label<S: Into<String>>(self, label: S) -> OtocoOrder {
+        OtocoOrder {
+            label: Some(label.into()),
+            ..self
+        }
+    }
+
+    pub fn reduce_only(self) -> OtocoOrder {
+        OtocoOrder {
+            reduce_only: Some(true),
+            ..self
         }
     }
 }
@@ -241,6 +559,7 @@ pub struct Trade {
     pub timestamp: u64,
     pub trade_id: String,
     pub trade_seq: i64,
+    pub block_trade_id: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -272,6 +591,16 @@ pub struct Order {
     pub trigger: Option<Trigger>,
     pub triggered: Option<bool>,
     pub usd: Option<f64>,
+    pub trigger_offset: Option<f64>,
+    pub trigger_price: Option<f64>,
+    pub reject_post_only: Option<bool>,
+    pub mmp: Option<bool>,
+    pub mmp_cancelled: Option<bool>,
+    pub display_amount: Option<f64>,
+    pub oto_order_ids: Option<Vec<String>>,
+    pub oco_ref: Option<String>,
+    pub primary_order_id: Option<String>,
+    pub is_secondary_oto: Option<bool>,
 }
 
 fn deserialize_price<'de, D>(de: D) -> Result<Option<f64>, D::Error>
@@ -341,7 +670,7 @@ impl Request for CancelAllByInstrumentRequest {
     type Response = CancelAllResponse;
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct CancelAllByCurrencyRequest {
     pub currency: Currency,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -354,18 +683,29 @@ impl Request for CancelAllByCurrencyRequest {
     type Response = CancelAllResponse;
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
-pub struct CancelAllResponse(usize);
+/// The number of cancelled orders
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Shrinkwrap)]
+pub struct CancelAllResponse(pub usize);
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct CancelByLabelRequest {
-    label: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
 }
 
 impl CancelByLabelRequest {
     pub fn new<S: Into<String>>(label: S) -> Self {
         Self {
             label: label.into(),
+            currency: None,
+        }
+    }
+
+    pub fn with_currency<S: Into<String>>(label: S, currency: Currency) -> Self {
+        Self {
+            label: label.into(),
+            currency: Some(currency),
         }
     }
 }
@@ -397,6 +737,123 @@ impl Request for GetOrderStateRequest {
     type Response = GetOrderStateResponse;
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClosePositionType {
+    Limit,
+    Market,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ClosePositionRequest {
+    pub instrument_name: String,
+    pub r#type: ClosePositionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+}
+
+impl ClosePositionRequest {
+    pub fn market(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            r#type: ClosePositionType::Market,
+            price: None,
+        }
+    }
+
+    pub fn limit(instrument_name: &str, price: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            r#type: ClosePositionType::Limit,
+            price: Some(price),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Shrinkwrap)]
+#[shrinkwrap(mutable)]
+pub struct ClosePositionResponse(pub TradeResponse);
+
+impl Request for ClosePositionRequest {
+    const METHOD: &'static str = "private/close_position";
+    type Response = ClosePositionResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetMarginsRequest {
+    pub instrument_name: String,
+    pub amount: f64,
+    pub price: f64,
+}
+
+impl GetMarginsRequest {
+    pub fn new(instrument_name: &str, amount: f64, price: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            amount,
+            price,
+        }
+    }
+}
+
+impl TryFrom<&TradeRequest> for GetMarginsRequest {
+    type Error = DeribitError;
+
+    /// Margin estimation for a hypothetical order. Orders without a price, like market
+    /// orders, are refused, use `GetMarginsRequest::new` to estimate them at a given price.
+    fn try_from(req: &TradeRequest) -> Result<Self, DeribitError> {
+        let price = req
+            .price
+            .ok_or_else(|| DeribitError::MissingPrice(req.instrument_name.clone()))?;
+        Ok(Self::new(&req.instrument_name, req.amount, price))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetMarginsResponse {
+    pub buy: f64,
+    pub max_price: f64,
+    pub min_price: f64,
+    pub sell: f64,
+}
+
+impl Request for GetMarginsRequest {
+    const METHOD: &'static str = "private/get_margins";
+    type Response = GetMarginsResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetOrderMarginByIdsRequest {
+    pub ids: Vec<String>,
+}
+
+impl GetOrderMarginByIdsRequest {
+    pub fn new<S: Into<String>, I: IntoIterator<Item = S>>(ids: I) -> Self {
+        Self {
+            ids: ids.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&[Order]> for GetOrderMarginByIdsRequest {
+    fn from(orders: &[Order]) -> Self {
+        Self::new(orders.iter().map(|o| o.order_id.clone()))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OrderMargin {
+    pub order_id: String,
+    pub initial_margin: f64,
+}
+
+pub type GetOrderMarginByIdsResponse = Vec<OrderMargin>;
+
+impl Request for GetOrderMarginByIdsRequest {
+    const METHOD: &'static str = "private/get_order_margin_by_ids";
+    type Response = GetOrderMarginByIdsResponse;
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum GetOpenOrderType {
@@ -472,3 +929,638 @@ impl Request for GetOpenOrdersByInstrumentRequest {
     const METHOD: &'static str = "private/get_open_orders_by_instrument";
     type Response = Vec<GetOpenOrdersByInstrumentResponse>;
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetOpenOrdersByLabelRequest {
+    pub currency: Currency,
+    pub label: String,
+}
+
+impl GetOpenOrdersByLabelRequest {
+    pub fn new<S: Into<String>>(currency: Currency, label: S) -> Self {
+        Self {
+            currency,
+            label: label.into(),
+        }
+    }
+}
+
+pub type GetOpenOrdersByLabelResponse = Vec<Order>;
+
+impl Request for GetOpenOrdersByLabelRequest {
+    const METHOD: &'static str = "private/get_open_orders_by_label";
+    type Response = GetOpenOrdersByLabelResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetOrderHistoryByCurrencyRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_unfilled: Option<bool>,
+}
+
+impl GetOrderHistoryByCurrencyRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_kind(currency: Currency, kind: AssetKind) -> Self {
+        Self {
+            currency,
+            kind: Some(kind),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetOrderHistoryByCurrencyResponse = Vec<Order>;
+
+impl Request for GetOrderHistoryByCurrencyRequest {
+    const METHOD: &'static str = "private/get_order_history_by_currency";
+    type Response = GetOrderHistoryByCurrencyResponse;
+}
+
+impl PaginatedRequest for GetOrderHistoryByCurrencyRequest {
+    type Item = Order;
+
+    #[throws(Error)]
+    fn paginate(&self, orders: Vec<Order>) -> (Vec<Order>, Option<Self>) {
+        let next = next_offset(self.count, self.offset, orders.len(), None).map(|offset| Self {
+            offset: Some(offset),
+            ..self.clone()
+        });
+        (orders, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetOrderHistoryByInstrumentRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_unfilled: Option<bool>,
+}
+
+impl GetOrderHistoryByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetOrderHistoryByInstrumentResponse = Vec<Order>;
+
+impl Request for GetOrderHistoryByInstrumentRequest {
+    const METHOD: &'static str = "private/get_order_history_by_instrument";
+    type Response = GetOrderHistoryByInstrumentResponse;
+}
+
+impl PaginatedRequest for GetOrderHistoryByInstrumentRequest {
+    type Item = Order;
+
+    #[throws(Error)]
+    fn paginate(&self, orders: Vec<Order>) -> (Vec<Order>, Option<Self>) {
+        let next = next_offset(self.count, self.offset, orders.len(), None).map(|offset| Self {
+            offset: Some(offset),
+            ..self.clone()
+        });
+        (orders, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetStopOrderHistoryRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instrument_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+impl GetStopOrderHistoryRequest {
+    pub fn by_currency(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+
+    pub fn by_instrument(currency: Currency, instrument_name: &str) -> Self {
+        Self {
+            currency,
+            instrument_name: Some(instrument_name.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct StopOrderHistoryEntry {
+    pub amount: f64,
+    pub direction: Direction,
+    pub instrument_name: String,
+    pub order_id: Option<String>,
+    pub order_state: String,
+    #[serde(default, deserialize_with = "deserialize_price")]
+    pub price: Option<f64>, // None for market orders
+    pub request: String,
+    #[serde(alias = "trigger_order_id")]
+    pub stop_id: String,
+    #[serde(alias = "trigger_price")]
+    pub stop_price: Option<f64>,
+    pub timestamp: u64,
+    pub trigger: Option<Trigger>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetStopOrderHistoryResponse {
+    pub entries: Vec<StopOrderHistoryEntry>,
+    pub continuation: Option<String>,
+}
+
+impl Request for GetStopOrderHistoryRequest {
+    const METHOD: &'static str = "private/get_stop_order_history";
+    type Response = GetStopOrderHistoryResponse;
+}
+
+impl PaginatedRequest for GetStopOrderHistoryRequest {
+    type Item = StopOrderHistoryEntry;
+
+    #[throws(Error)]
+    fn paginate(
+        &self,
+        resp: GetStopOrderHistoryResponse,
+    ) -> (Vec<StopOrderHistoryEntry>, Option<Self>) {
+        let next = next_continuation(resp.continuation, resp.entries.len()).map(|c| Self {
+            continuation: Some(c),
+            ..self.clone()
+        });
+        (resp.entries, next)
+    }
+}
+
+pub(crate) fn next_continuation(continuation: Option<String>, len: usize) -> Option<String> {
+    continuation.filter(|c| len > 0 && c != "none")
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetUserTradesResponse {
+    pub trades: Vec<Trade>,
+    pub has_more: bool,
+}
+
+// Splits a page of trades, dropping the ones already yielded by the previous page.
+// Returns the trades and the ids sharing the last cursor value, which the next page has to skip.
+fn dedup_trades<K: PartialEq>(
+    resp: GetUserTradesResponse,
+    skip: &[String],
+    cursor: impl Fn(&Trade) -> K,
+) -> (Vec<Trade>, Option<(K, Vec<String>)>) {
+    let has_more = resp.has_more;
+    let boundary = resp.trades.last().map(|t| {
+        let k = cursor(t);
+        let ids = resp
+            .trades
+            .iter()
+            .filter(|t| cursor(t) == k)
+            .map(|t| t.trade_id.clone())
+            .collect();
+        (k, ids)
+    });
+    let trades = resp
+        .trades
+        .into_iter()
+        .filter(|t| !skip.contains(&t.trade_id))
+        .collect();
+    (trades, boundary.filter(|_| has_more))
+}
+
+// The largest and the default number of trades the API returns in a page
+const MAX_TRADES_COUNT: u64 = 1000;
+const DEFAULT_TRADES_COUNT: u64 = 10;
+
+// A page made only of already returned trades means more trades share one timestamp than fit
+// in a page, so the same timestamp is requested again with larger pages. Past the largest page
+// the trades of that timestamp cannot all be reached, which is an error rather than a gap.
+#[throws(Error)]
+fn next_page_count(count: Option<u64>, trades: &[Trade], timestamp: u64) -> Option<u64> {
+    if !trades.is_empty() {
+        return count;
+    }
+    let count = count.unwrap_or(DEFAULT_TRADES_COUNT);
+    if count >= MAX_TRADES_COUNT {
+        throw!(DeribitError::PaginationStalled {
+            timestamp,
+            count: MAX_TRADES_COUNT,
+        });
+    }
+    Some((count * 2).min(MAX_TRADES_COUNT))
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetUserTradesByCurrencyRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+    /// Trades returned by the previous page sharing its last cursor value, skipped when paginating
+    #[serde(skip)]
+    pub skip_trade_ids: Vec<String>,
+}
+
+impl GetUserTradesByCurrencyRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            sorting: Some(Sorting::Asc),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_kind(currency: Currency, kind: AssetKind) -> Self {
+        Self {
+            kind: Some(kind),
+            ..Self::new(currency)
+        }
+    }
+}
+
+pub type GetUserTradesByCurrencyResponse = GetUserTradesResponse;
+
+impl Request for GetUserTradesByCurrencyRequest {
+    const METHOD: &'static str = "private/get_user_trades_by_currency";
+    type Response = GetUserTradesByCurrencyResponse;
+}
+
+impl PaginatedRequest for GetUserTradesByCurrencyRequest {
+    type Item = Trade;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetUserTradesResponse) -> (Vec<Trade>, Option<Self>) {
+        let (trades, boundary) = dedup_trades(resp, &self.skip_trade_ids, |t| t.trade_id.clone());
+        let next = boundary
+            .filter(|_| !trades.is_empty())
+            .map(|(id, skip)| match self.sorting {
+                Some(Sorting::Desc) => Self {
+                    end_id: Some(id),
+                    skip_trade_ids: skip,
+                    ..self.clone()
+                },
+                _ => Self {
+                    start_id: Some(id),
+                    skip_trade_ids: skip,
+                    ..self.clone()
+                },
+            });
+        (trades, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetUserTradesByCurrencyAndTimeRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+    /// Trades returned by the previous page sharing its last cursor value, skipped when paginating
+    #[serde(skip)]
+    pub skip_trade_ids: Vec<String>,
+}
+
+impl GetUserTradesByCurrencyAndTimeRequest {
+    pub fn new(currency: Currency, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            currency,
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            sorting: Some(Sorting::Asc),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetUserTradesByCurrencyAndTimeResponse = GetUserTradesResponse;
+
+impl Request for GetUserTradesByCurrencyAndTimeRequest {
+    const METHOD: &'static str = "private/get_user_trades_by_currency_and_time";
+    type Response = GetUserTradesByCurrencyAndTimeResponse;
+}
+
+impl PaginatedRequest for GetUserTradesByCurrencyAndTimeRequest {
+    type Item = Trade;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetUserTradesResponse) -> (Vec<Trade>, Option<Self>) {
+        let (trades, boundary) = dedup_trades(resp, &self.skip_trade_ids, |t| t.timestamp);
+        let next = match boundary {
+            Some((timestamp, skip)) => {
+                let count = next_page_count(self.count, &trades, timestamp)?;
+                Some(match self.sorting {
+                    Some(Sorting::Desc) => Self {
+                        end_timestamp: timestamp,
+                        count,
+                        skip_trade_ids: skip,
+                        ..self.clone()
+                    },
+                    _ => Self {
+                        start_timestamp: timestamp,
+                        count,
+                        skip_trade_ids: skip,
+                        ..self.clone()
+                    },
+                })
+            }
+            None => None,
+        };
+        (trades, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetUserTradesByInstrumentRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_seq: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_seq: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetUserTradesByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            sorting: Some(Sorting::Asc),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetUserTradesByInstrumentResponse = GetUserTradesResponse;
+
+impl Request for GetUserTradesByInstrumentRequest {
+    const METHOD: &'static str = "private/get_user_trades_by_instrument";
+    type Response = GetUserTradesByInstrumentResponse;
+}
+
+impl PaginatedRequest for GetUserTradesByInstrumentRequest {
+    type Item = Trade;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetUserTradesResponse) -> (Vec<Trade>, Option<Self>) {
+        // Trade sequence numbers are unique per instrument, so the cursor can step over the last one
+        let next = match resp.trades.last() {
+            Some(last) if resp.has_more => Some(match self.sorting {
+                Some(Sorting::Desc) => Self {
+                    end_seq: Some(last.trade_seq - 1),
+                    ..self.clone()
+                },
+                _ => Self {
+                    start_seq: Some(last.trade_seq + 1),
+                    ..self.clone()
+                },
+            }),
+            _ => None,
+        };
+        (resp.trades, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetUserTradesByInstrumentAndTimeRequest {
+    pub instrument_name: String,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+    /// Trades returned by the previous page sharing its last cursor value, skipped when paginating
+    #[serde(skip)]
+    pub skip_trade_ids: Vec<String>,
+}
+
+impl GetUserTradesByInstrumentAndTimeRequest {
+    pub fn new(instrument_name: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            sorting: Some(Sorting::Asc),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetUserTradesByInstrumentAndTimeResponse = GetUserTradesResponse;
+
+impl Request for GetUserTradesByInstrumentAndTimeRequest {
+    const METHOD: &'static str = "private/get_user_trades_by_instrument_and_time";
+    type Response = GetUserTradesByInstrumentAndTimeResponse;
+}
+
+impl PaginatedRequest for GetUserTradesByInstrumentAndTimeRequest {
+    type Item = Trade;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetUserTradesResponse) -> (Vec<Trade>, Option<Self>) {
+        let (trades, boundary) = dedup_trades(resp, &self.skip_trade_ids, |t| t.timestamp);
+        let next = match boundary {
+            Some((timestamp, skip)) => {
+                let count = next_page_count(self.count, &trades, timestamp)?;
+                Some(match self.sorting {
+                    Some(Sorting::Desc) => Self {
+                        end_timestamp: timestamp,
+                        count,
+                        skip_trade_ids: skip,
+                        ..self.clone()
+                    },
+                    _ => Self {
+                        start_timestamp: timestamp,
+                        count,
+                        skip_trade_ids: skip,
+                        ..self.clone()
+                    },
+                })
+            }
+            None => None,
+        };
+        (trades, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetUserTradesByOrderRequest {
+    pub order_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetUserTradesByOrderRequest {
+    pub fn new(order_id: &str) -> Self {
+        Self {
+            order_id: order_id.to_string(),
+            sorting: None,
+        }
+    }
+}
+
+pub type GetUserTradesByOrderResponse = Vec<Trade>;
+
+impl Request for GetUserTradesByOrderRequest {
+    const METHOD: &'static str = "private/get_user_trades_by_order";
+    type Response = GetUserTradesByOrderResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementType {
+    Settlement,
+    Delivery,
+    Bankruptcy,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Settlement {
+    pub r#type: SettlementType,
+    pub timestamp: u64,
+    pub session_profit_loss: f64,
+    pub instrument_name: Option<String>,
+    pub position: Option<f64>,
+    pub profit_loss: Option<f64>,
+    pub mark_price: Option<f64>,
+    pub index_price: Option<f64>,
+    pub funding: Option<f64>,
+    pub funded: Option<f64>,
+    pub socialized: Option<f64>,
+    pub session_bankrupcy: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetSettlementHistoryResponse {
+    pub settlements: Vec<Settlement>,
+    pub continuation: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetSettlementHistoryByInstrumentRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<SettlementType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+impl GetSettlementHistoryByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetSettlementHistoryByInstrumentResponse = GetSettlementHistoryResponse;
+
+impl Request for GetSettlementHistoryByInstrumentRequest {
+    const METHOD: &'static str = "private/get_settlement_history_by_instrument";
+    type Response = GetSettlementHistoryByInstrumentResponse;
+}
+
+impl PaginatedRequest for GetSettlementHistoryByInstrumentRequest {
+    type Item = Settlement;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetSettlementHistoryResponse) -> (Vec<Settlement>, Option<Self>) {
+        let next = next_continuation(resp.continuation, resp.settlements.len()).map(|c| Self {
+            continuation: Some(c),
+            ..self.clone()
+        });
+        (resp.settlements, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetSettlementHistoryByCurrencyRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<SettlementType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+impl GetSettlementHistoryByCurrencyRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+}
+
+pub type GetSettlementHistoryByCurrencyResponse = GetSettlementHistoryResponse;
+
+impl Request for GetSettlementHistoryByCurrencyRequest {
+    const METHOD: &'static str = "private/get_settlement_history_by_currency";
+    type Response = GetSettlementHistoryByCurrencyResponse;
+}
+
+impl PaginatedRequest for GetSettlementHistoryByCurrencyRequest {
+    type Item = Settlement;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetSettlementHistoryResponse) -> (Vec<Settlement>, Option<Self>) {
+        let next = next_continuation(resp.continuation, resp.settlements.len()).map(|c| Self {
+            continuation: Some(c),
+            ..self.clone()
+        });
+        (resp.settlements, next)
+    }
+}
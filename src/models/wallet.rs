@@ -1,15 +1,18 @@
 use crate::models::{
-    Currency, Direction, Priority, Request, TransferState, TransferType, WithdrawState,
+    next_offset, Currency, DepositState, PaginatedRequest, Priority, Request, TransferDirection,
+    TransferState, TransferType, WithdrawState,
 };
+use failure::Error;
+use fehler::throws;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GetTransfersRequest {
-    currency: Currency,
+    pub currency: Currency,
     #[serde(skip_serializing_if = "Option::is_none")]
-    count: Option<u64>,
+    pub count: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    offset: Option<u64>,
+    pub offset: Option<u64>,
 }
 
 impl GetTransfersRequest {
@@ -24,21 +27,22 @@ impl GetTransfersRequest {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GetTransfersResponse {
-    count: u64,
-    data: Vec<TransferItem>,
+    /// The total number of transfers
+    pub count: u64,
+    pub data: Vec<TransferItem>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct TransferItem {
-    amount: f64,
-    created_timestamp: u64,
-    currency: Currency,
-    direction: Direction,
-    id: i64,
-    other_side: String,
-    state: TransferState,
-    r#type: TransferType,
-    updated_timestamp: i64,
+    pub amount: f64,
+    pub created_timestamp: u64,
+    pub currency: Currency,
+    pub direction: TransferDirection,
+    pub id: i64,
+    pub other_side: String,
+    pub state: TransferState,
+    pub r#type: TransferType,
+    pub updated_timestamp: i64,
 }
 
 impl Request for GetTransfersRequest {
@@ -46,11 +50,63 @@ impl Request for GetTransfersRequest {
     type Response = GetTransfersResponse;
 }
 
+impl PaginatedRequest for GetTransfersRequest {
+    type Item = TransferItem;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetTransfersResponse) -> (Vec<TransferItem>, Option<Self>) {
+        let next =
+            next_offset(self.count, self.offset, resp.data.len(), Some(resp.count)).map(|offset| {
+                Self {
+                    offset: Some(offset),
+                    ..self.clone()
+                }
+            });
+        (resp.data, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CancelTransferByIdRequest {
+    pub currency: Currency,
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tfa: Option<String>,
+}
+
+impl CancelTransferByIdRequest {
+    pub fn new(currency: Currency, id: i64) -> Self {
+        Self {
+            currency,
+            id,
+            tfa: None,
+        }
+    }
+}
+
+pub type CancelTransferByIdResponse = TransferItem;
+
+impl Request for CancelTransferByIdRequest {
+    const METHOD: &'static str = "private/cancel_transfer_by_id";
+    type Response = CancelTransferByIdResponse;
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SubmitTransferToSubaccountRequest {
-    currency: Currency,
-    amount: f64,
-    destination: u64,
+    pub currency: Currency,
+    pub amount: f64,
+    /// Id of the destination subaccount
+    pub destination: u64,
+}
+
+impl SubmitTransferToSubaccountRequest {
+    pub fn new(currency: Currency, amount: f64, destination: u64) -> Self {
+        Self {
+            currency,
+            amount,
+            destination,
+        }
+    }
 }
 
 pub type SubmitTransferToSubaccountResponse = TransferItem;
@@ -60,13 +116,54 @@ impl Request for SubmitTransferToSubaccountRequest {
     type Response = SubmitTransferToSubaccountResponse;
 }
 
+/// Moves funds between two subaccounts of the main account, `source` defaulting to the
+/// authenticated one.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SubmitTransferBetweenSubaccountsRequest {
+    pub currency: Currency,
+    pub amount: f64,
+    pub destination: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<u64>,
+}
+
+impl SubmitTransferBetweenSubaccountsRequest {
+    pub fn new(currency: Currency, amount: f64, source: u64, destination: u64) -> Self {
+        Self {
+            currency,
+            amount,
+            destination,
+            source: Some(source),
+        }
+    }
+}
+
+pub type SubmitTransferBetweenSubaccountsResponse = TransferItem;
+
+impl Request for SubmitTransferBetweenSubaccountsRequest {
+    const METHOD: &'static str = "private/submit_transfer_between_subaccounts";
+    type Response = SubmitTransferBetweenSubaccountsResponse;
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SubmitTransferToUserRequest {
-    currency: Currency,
-    amount: f64,
-    destination: u64,
+    pub currency: Currency,
+    pub amount: f64,
+    /// Wallet address of the destination user
+    pub destination: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tfa: Option<String>,
+    pub tfa: Option<String>,
+}
+
+impl SubmitTransferToUserRequest {
+    pub fn new(currency: Currency, amount: f64, destination: &str) -> Self {
+        Self {
+            currency,
+            amount,
+            destination: destination.to_string(),
+            tfa: None,
+        }
+    }
 }
 
 pub type SubmitTransferToUserResponse = TransferItem;
@@ -78,31 +175,326 @@ impl Request for SubmitTransferToUserRequest {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct WithdrawRequest {
-    currency: Currency,
-    address: String,
-    amount: f64,
-    priority: Priority,
-    destination: u64,
+    pub currency: Currency,
+    pub address: String,
+    pub amount: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tfa: Option<String>,
+    pub priority: Option<Priority>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tfa: Option<String>,
+}
+
+impl WithdrawRequest {
+    /// Withdraws to an address of the address book.
+    pub fn new(currency: Currency, address: &str, amount: f64) -> Self {
+        Self {
+            currency,
+            address: address.to_string(),
+            amount,
+            priority: None,
+            tfa: None,
+        }
+    }
+
+    /// Withdraws with a priority, which sets the fee of BTC withdrawals.
+    pub fn with_priority(
+        currency: Currency,
+        address: &str,
+        amount: f64,
+        priority: Priority,
+    ) -> Self {
+        Self {
+            priority: Some(priority),
+            ..Self::new(currency, address, amount)
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct WithdrawResponse {
-    address: String,
-    amount: f64,
-    confirmed_timestamp: u64,
-    created_timestamp: u64,
-    currency: Currency,
-    fee: f64,
-    id: u64,
-    priority: u64,
-    state: WithdrawState,
-    transaction_id: String,
-    updated_timestamp: u64,
+pub struct Withdrawal {
+    pub address: String,
+    pub amount: f64,
+    pub confirmed_timestamp: Option<u64>,
+    pub created_timestamp: u64,
+    pub currency: Currency,
+    pub fee: f64,
+    pub id: u64,
+    pub priority: f64,
+    pub state: WithdrawState,
+    pub transaction_id: Option<String>,
+    pub updated_timestamp: u64,
 }
 
+pub type WithdrawResponse = Withdrawal;
+
 impl Request for WithdrawRequest {
-    const METHOD: &'static str = "/private/withdraw";
+    const METHOD: &'static str = "private/withdraw";
     type Response = WithdrawResponse;
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CancelWithdrawalRequest {
+    pub currency: Currency,
+    pub id: u64,
+}
+
+impl CancelWithdrawalRequest {
+    pub fn new(currency: Currency, id: u64) -> Self {
+        Self { currency, id }
+    }
+}
+
+pub type CancelWithdrawalResponse = Withdrawal;
+
+impl Request for CancelWithdrawalRequest {
+    const METHOD: &'static str = "private/cancel_withdrawal";
+    type Response = CancelWithdrawalResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetWithdrawalsRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl GetWithdrawalsRequest {
+    pub fn with_currency(currency: Currency) -> Self {
+        Self {
+            currency,
+            count: None,
+            offset: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetWithdrawalsResponse {
+    /// The total number of withdrawals
+    pub count: u64,
+    pub data: Vec<Withdrawal>,
+}
+
+impl Request for GetWithdrawalsRequest {
+    const METHOD: &'static str = "private/get_withdrawals";
+    type Response = GetWithdrawalsResponse;
+}
+
+impl PaginatedRequest for GetWithdrawalsRequest {
+    type Item = Withdrawal;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetWithdrawalsResponse) -> (Vec<Withdrawal>, Option<Self>) {
+        let next =
+            next_offset(self.count, self.offset, resp.data.len(), Some(resp.count)).map(|offset| {
+                Self {
+                    offset: Some(offset),
+                    ..self.clone()
+                }
+            });
+        (resp.data, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Deposit {
+    pub address: String,
+    pub amount: f64,
+    pub currency: Currency,
+    pub received_timestamp: u64,
+    pub state: DepositState,
+    pub transaction_id: Option<String>,
+    pub updated_timestamp: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetDepositsRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl GetDepositsRequest {
+    pub fn with_currency(currency: Currency) -> Self {
+        Self {
+            currency,
+            count: None,
+            offset: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetDepositsResponse {
+    /// The total number of deposits
+    pub count: u64,
+    pub data: Vec<Deposit>,
+}
+
+impl Request for GetDepositsRequest {
+    const METHOD: &'static str = "private/get_deposits";
+    type Response = GetDepositsResponse;
+}
+
+impl PaginatedRequest for GetDepositsRequest {
+    type Item = Deposit;
+
+    #[throws(Error)]
+    fn paginate(&self, resp: GetDepositsResponse) -> (Vec<Deposit>, Option<Self>) {
+        let next =
+            next_offset(self.count, self.offset, resp.data.len(), Some(resp.count)).map(|offset| {
+                Self {
+                    offset: Some(offset),
+                    ..self.clone()
+                }
+            });
+        (resp.data, next)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DepositAddress {
+    pub address: String,
+    pub creation_timestamp: u64,
+    pub currency: Currency,
+    pub r#type: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CreateDepositAddressRequest {
+    pub currency: Currency,
+}
+
+impl CreateDepositAddressRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self { currency }
+    }
+}
+
+/// `None` if no new address could be created.
+pub type CreateDepositAddressResponse = Option<DepositAddress>;
+
+impl Request for CreateDepositAddressRequest {
+    const METHOD: &'static str = "private/create_deposit_address";
+    type Response = CreateDepositAddressResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetCurrentDepositAddressRequest {
+    pub currency: Currency,
+}
+
+impl GetCurrentDepositAddressRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self { currency }
+    }
+}
+
+/// `None` if the account has no deposit address yet.
+pub type GetCurrentDepositAddressResponse = Option<DepositAddress>;
+
+impl Request for GetCurrentDepositAddressRequest {
+    const METHOD: &'static str = "private/get_current_deposit_address";
+    type Response = GetCurrentDepositAddressResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressBookType {
+    Transfer,
+    Withdrawal,
+    DepositSource,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AddressBookEntry {
+    pub address: String,
+    pub currency: Currency,
+    pub r#type: AddressBookType,
+    pub label: Option<String>,
+    pub creation_timestamp: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AddToAddressBookRequest {
+    pub currency: Currency,
+    pub r#type: AddressBookType,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tfa: Option<String>,
+}
+
+impl AddToAddressBookRequest {
+    pub fn new(currency: Currency, r#type: AddressBookType, address: &str, label: &str) -> Self {
+        Self {
+            currency,
+            r#type,
+            address: address.to_string(),
+            label: Some(label.to_string()),
+            tfa: None,
+        }
+    }
+}
+
+pub type AddToAddressBookResponse = AddressBookEntry;
+
+impl Request for AddToAddressBookRequest {
+    const METHOD: &'static str = "private/add_to_address_book";
+    type Response = AddToAddressBookResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetAddressBookRequest {
+    pub currency: Currency,
+    pub r#type: AddressBookType,
+}
+
+impl GetAddressBookRequest {
+    pub fn new(currency: Currency, r#type: AddressBookType) -> Self {
+        Self { currency, r#type }
+    }
+}
+
+pub type GetAddressBookResponse = Vec<AddressBookEntry>;
+
+impl Request for GetAddressBookRequest {
+    const METHOD: &'static str = "private/get_address_book";
+    type Response = GetAddressBookResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RemoveFromAddressBookRequest {
+    pub currency: Currency,
+    pub r#type: AddressBookType,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tfa: Option<String>,
+}
+
+impl RemoveFromAddressBookRequest {
+    pub fn new(currency: Currency, r#type: AddressBookType, address: &str) -> Self {
+        Self {
+            currency,
+            r#type,
+            address: address.to_string(),
+            tfa: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoveFromAddressBookResponse {
+    Ok,
+}
+
+impl Request for RemoveFromAddressBookRequest {
+    const METHOD: &'static str = "private/remove_from_address_book";
+    type Response = RemoveFromAddressBookResponse;
+}
@@ -0,0 +1,121 @@
+use crate::errors::DeribitError;
+use crate::models::subscription::{BookData, Delta, OrderBookDelta};
+use crate::models::GetOrderBookResponse;
+use fehler::{throw, throws};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Price(f64);
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Price {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// A local copy of an order book, seeded from `GetOrderBookRequest` and kept up to date
+/// with the `book.{instrument_name}.{interval}` notifications.
+///
+/// Notifications older than the snapshot are ignored, and a gap in the change ids is
+/// reported as `DeribitError::OrderBookOutOfSync`, after which the book has to be seeded again.
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    instrument_name: String,
+    change_id: i64,
+    timestamp: u64,
+    bids: BTreeMap<Price, f64>,
+    asks: BTreeMap<Price, f64>,
+}
+
+impl OrderBook {
+    pub fn from_snapshot(snapshot: &GetOrderBookResponse) -> Self {
+        Self {
+            instrument_name: snapshot.ticker.instrument_name.clone(),
+            change_id: snapshot.change_id,
+            timestamp: snapshot.ticker.timestamp,
+            bids: levels(&snapshot.bids),
+            asks: levels(&snapshot.asks),
+        }
+    }
+
+    /// Applies a `book.*` notification, the first notification of a subscription being a
+    /// full snapshot without `prev_change_id`.
+    #[throws(DeribitError)]
+    pub fn apply(&mut self, data: &BookData) {
+        if data.instrument_name != self.instrument_name || data.change_id <= self.change_id {
+            return;
+        }
+        match data.prev_change_id {
+            None => {
+                self.bids.clear();
+                self.asks.clear();
+            }
+            Some(prev) if prev == self.change_id => {}
+            prev_change_id => throw!(DeribitError::OrderBookOutOfSync {
+                instrument_name: self.instrument_name.clone(),
+                expected: self.change_id,
+                prev_change_id,
+            }),
+        }
+        apply_deltas(&mut self.bids, &data.bids);
+        apply_deltas(&mut self.asks, &data.asks);
+        self.change_id = data.change_id;
+        self.timestamp = data.timestamp;
+    }
+
+    pub fn instrument_name(&self) -> &str {
+        &self.instrument_name
+    }
+
+    pub fn change_id(&self) -> i64 {
+        self.change_id
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// The bids as `(price, amount)`, best first.
+    pub fn bids(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.bids.iter().rev().map(|(p, a)| (p.0, *a))
+    }
+
+    /// The asks as `(price, amount)`, best first.
+    pub fn asks(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.asks.iter().map(|(p, a)| (p.0, *a))
+    }
+
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids().next()
+    }
+
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks().next()
+    }
+
+    pub fn mid_price(&self) -> Option<f64> {
+        Some((self.best_bid()?.0 + self.best_ask()?.0) / 2.)
+    }
+}
+
+fn levels(levels: &[(f64, f64)]) -> BTreeMap<Price, f64> {
+    levels.iter().map(|&(p, a)| (Price(p), a)).collect()
+}
+
+fn apply_deltas(side: &mut BTreeMap<Price, f64>, deltas: &[OrderBookDelta]) {
+    for OrderBookDelta(delta, price, amount) in deltas {
+        match delta {
+            Delta::New | Delta::Change => side.insert(Price(*price), *amount),
+            Delta::Delete => side.remove(&Price(*price)),
+        };
+    }
+}
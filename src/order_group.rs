@@ -0,0 +1,71 @@
+use crate::models::{
+    CancelByLabelRequest, Currency, EditByLabelRequest, EditResponse, GetOpenOrdersByLabelRequest,
+    Order, TradeRequest,
+};
+use crate::DeribitAPIClient;
+use failure::Error;
+use fehler::throws;
+
+/// A set of orders sharing one label, so that a strategy can manage its own orders
+/// on an account shared with other strategies.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OrderGroup {
+    label: String,
+}
+
+impl OrderGroup {
+    pub fn new<S: Into<String>>(label: S) -> Self {
+        Self {
+            label: label.into(),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Assigns the group label to a request, a `BuyRequest` or `SellRequest` is tagged
+    /// through `deref_mut()`.
+    pub fn tag(&self, request: &mut TradeRequest) {
+        request.label = Some(self.label.clone());
+    }
+
+    /// The open orders of the group across the given currencies.
+    #[throws(Error)]
+    pub async fn open_orders(
+        &self,
+        client: &mut DeribitAPIClient,
+        currencies: &[Currency],
+    ) -> Vec<Order> {
+        let mut orders = vec![];
+        for currency in currencies {
+            let req = GetOpenOrdersByLabelRequest::new(currency.clone(), &*self.label);
+            orders.extend(client.call(req).await?.await?);
+        }
+        orders
+    }
+
+    /// Edits the order of the group resting on `instrument_name`.
+    #[throws(Error)]
+    pub async fn edit(
+        &self,
+        client: &mut DeribitAPIClient,
+        instrument_name: &str,
+        price: f64,
+        amount: f64,
+    ) -> EditResponse {
+        let req = EditByLabelRequest::new(&self.label, instrument_name, price, amount);
+        client.call(req).await?.await?
+    }
+
+    /// Cancels the orders of the group, optionally only the ones in `currency`.
+    /// Returns the number of cancelled orders.
+    #[throws(Error)]
+    pub async fn cancel(&self, client: &mut DeribitAPIClient, currency: Option<Currency>) -> usize {
+        let req = CancelByLabelRequest {
+            label: self.label.clone(),
+            currency,
+        };
+        client.call(req).await?.await?.0
+    }
+}
@@ -0,0 +1,245 @@
+//! Black-76 pricing for Deribit's coin-settled (inverse) options.
+//!
+//! Deribit options are written on the USD forward but premiums are paid in the base coin,
+//! so the coin price of an option is its USD price divided by the forward.
+//! Volatilities here are fractions (`0.65` for 65%), while `mark_iv` in `TickerData` is a percentage.
+
+mod vol_surface;
+
+pub use vol_surface::{VolSlice, VolSurface};
+
+use crate::models::subscription::TickerData;
+use crate::models::{InstrumentName, OptionType};
+use chrono::{DateTime, Utc};
+use std::f64::consts::PI;
+
+const SECONDS_PER_YEAR: f64 = 365. * 24. * 3600.;
+const IV_LOWER: f64 = 1e-6;
+const IV_UPPER: f64 = 20.;
+const IV_TOLERANCE: f64 = 1e-10;
+const IV_MAX_ITERATIONS: usize = 100;
+
+/// Year fraction (ACT/365) between `now` and `expiry`, floored at zero.
+pub fn time_to_expiry(expiry: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let seconds = (expiry - now).num_milliseconds() as f64 / 1000.;
+    (seconds / SECONDS_PER_YEAR).max(0.)
+}
+
+pub fn norm_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2. * PI).sqrt()
+}
+
+pub fn norm_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / 2f64.sqrt())
+}
+
+// Complementary error function with fractional error below 1.2e-7 (Numerical Recipes `erfcc`)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1. / (1. + 0.5 * z);
+    let r = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0. {
+        r
+    } else {
+        2. - r
+    }
+}
+
+/// Sensitivities of a single option.
+///
+/// `vega` is per one volatility point (1%) and `theta` is per calendar day,
+/// matching the greeks Deribit publishes in `TickerData`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OptionGreeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Black76 {
+    pub option_type: OptionType,
+    pub forward: f64,
+    pub strike: f64,
+    /// Time to expiry in years
+    pub time: f64,
+    pub vol: f64,
+}
+
+impl Black76 {
+    pub fn new(option_type: OptionType, forward: f64, strike: f64, time: f64, vol: f64) -> Self {
+        Self {
+            option_type,
+            forward,
+            strike,
+            time,
+            vol,
+        }
+    }
+
+    /// Builds the model for an option instrument, `None` if the instrument is not an option.
+    pub fn from_instrument(
+        instrument: &InstrumentName,
+        forward: f64,
+        vol: f64,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        Some(Self::new(
+            instrument.option_type()?,
+            forward,
+            instrument.strike()?,
+            time_to_expiry(instrument.expiry()?, now),
+            vol,
+        ))
+    }
+
+    /// Builds the model from an option ticker using its `underlying_price` and `mark_iv`.
+    pub fn from_ticker(ticker: &TickerData, now: DateTime<Utc>) -> Option<Self> {
+        let instrument: InstrumentName = ticker.instrument_name.parse().ok()?;
+        Self::from_instrument(
+            &instrument,
+            ticker.underlying_price?,
+            ticker.mark_iv? / 100.,
+            now,
+        )
+    }
+
+    pub fn with_vol(self, vol: f64) -> Self {
+        Self { vol, ..self }
+    }
+
+    fn d1_d2(&self) -> (f64, f64) {
+        let stdev = self.vol * self.time.sqrt();
+        let d1 = ((self.forward / self.strike).ln() + 0.5 * stdev * stdev) / stdev;
+        (d1, d1 - stdev)
+    }
+
+    fn intrinsic(&self) -> f64 {
+        (self.option_type.sign() * (self.forward - self.strike)).max(0.)
+    }
+
+    /// Undiscounted option value in USD.
+    pub fn price_usd(&self) -> f64 {
+        if self.time <= 0. || self.vol <= 0. {
+            return self.intrinsic();
+        }
+        let (d1, d2) = self.d1_d2();
+        let w = self.option_type.sign();
+        w * (self.forward * norm_cdf(w * d1) - self.strike * norm_cdf(w * d2))
+    }
+
+    /// Option value in the base coin, the unit Deribit quotes option prices in.
+    pub fn price_coin(&self) -> f64 {
+        self.price_usd() / self.forward
+    }
+
+    /// Greeks of the USD value with respect to the USD forward.
+    pub fn greeks_usd(&self) -> OptionGreeks {
+        let w = self.option_type.sign();
+        if self.time <= 0. || self.vol <= 0. {
+            let itm = w * (self.forward - self.strike) > 0.;
+            return OptionGreeks {
+                delta: if itm { w } else { 0. },
+                ..Default::default()
+            };
+        }
+        let (d1, _) = self.d1_d2();
+        let sqrt_t = self.time.sqrt();
+        let pdf = norm_pdf(d1);
+        OptionGreeks {
+            delta: if w > 0. {
+                norm_cdf(d1)
+            } else {
+                norm_cdf(d1) - 1.
+            },
+            gamma: pdf / (self.forward * self.vol * sqrt_t),
+            vega: self.forward * pdf * sqrt_t / 100.,
+            theta: -self.forward * pdf * self.vol / (2. * sqrt_t) / 365.,
+        }
+    }
+
+    /// Greeks of the coin value, as used in Deribit's account level `options_*` figures.
+    ///
+    /// Delta is the equivalent exposure in coins once the premium held in coin is accounted for,
+    /// i.e. the USD delta less the coin price of the option.
+    pub fn greeks_coin(&self) -> OptionGreeks {
+        let usd = self.greeks_usd();
+        let price = self.price_usd();
+        let f = self.forward;
+        OptionGreeks {
+            delta: usd.delta - price / f,
+            gamma: usd.gamma - usd.delta / f + price / (f * f),
+            vega: usd.vega / f,
+            theta: usd.theta / f,
+        }
+    }
+}
+
+/// Solves the Black-76 volatility reproducing `price` in USD.
+///
+/// Returns `None` if the price is outside the no-arbitrage bounds or the solver does not converge.
+pub fn implied_vol_usd(
+    option_type: OptionType,
+    forward: f64,
+    strike: f64,
+    time: f64,
+    price: f64,
+) -> Option<f64> {
+    if time <= 0. || forward <= 0. || strike <= 0. {
+        return None;
+    }
+    let model = Black76::new(option_type, forward, strike, time, 0.);
+    let upper_bound = match option_type {
+        OptionType::Call => forward,
+        OptionType::Put => strike,
+    };
+    if price <= model.intrinsic() || price >= upper_bound {
+        return None;
+    }
+
+    // Brenner-Subrahmanyam approximation as starting point, guarded by bisection
+    let (mut lo, mut hi) = (IV_LOWER, IV_UPPER);
+    let mut vol = ((2. * PI / time).sqrt() * price / forward).clamp(IV_LOWER, IV_UPPER);
+    for _ in 0..IV_MAX_ITERATIONS {
+        let model = model.with_vol(vol);
+        let diff = model.price_usd() - price;
+        if diff.abs() < IV_TOLERANCE * forward {
+            return Some(vol);
+        }
+        if diff > 0. {
+            hi = vol;
+        } else {
+            lo = vol;
+        }
+        let vega = model.greeks_usd().vega * 100.;
+        let newton = vol - diff / vega;
+        vol = if vega > 0. && newton > lo && newton < hi {
+            newton
+        } else {
+            0.5 * (lo + hi)
+        };
+    }
+    None
+}
+
+/// Solves the Black-76 volatility reproducing a coin-denominated `price`.
+pub fn implied_vol_coin(
+    option_type: OptionType,
+    forward: f64,
+    strike: f64,
+    time: f64,
+    price: f64,
+) -> Option<f64> {
+    implied_vol_usd(option_type, forward, strike, time, price * forward)
+}
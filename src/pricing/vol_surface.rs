@@ -0,0 +1,223 @@
+use crate::models::subscription::{MarkPriceOptionData, TickerData};
+use crate::models::{InstrumentName, OptionType};
+use crate::pricing::time_to_expiry;
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// The implied volatilities of all strikes sharing one expiry.
+#[derive(Debug, Clone, Default)]
+pub struct VolSlice {
+    forward: Option<f64>,
+    // (strike, vol, type of the option quoting it) sorted by strike
+    points: Vec<(f64, f64, OptionType)>,
+}
+
+impl VolSlice {
+    pub fn forward(&self) -> Option<f64> {
+        self.forward
+    }
+
+    /// The `(strike, vol)` points sorted by strike.
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.points.iter().map(|&(strike, vol, _)| (strike, vol))
+    }
+
+    // At a strike quoted by both a call and a put, the out of the money one wins, the call
+    // when the forward is unknown
+    fn insert(&mut self, strike: f64, vol: f64, option_type: OptionType, forward: Option<f64>) {
+        match self
+            .points
+            .binary_search_by(|(k, ..)| k.partial_cmp(&strike).unwrap_or(Ordering::Equal))
+        {
+            Ok(i) => {
+                let otm = match forward {
+                    Some(forward) if strike < forward => OptionType::Put,
+                    _ => OptionType::Call,
+                };
+                if self.points[i].2 == option_type || option_type == otm {
+                    self.points[i] = (strike, vol, option_type);
+                }
+            }
+            Err(i) => self.points.insert(i, (strike, vol, option_type)),
+        }
+    }
+
+    /// Linear interpolation in log-moneyness `ln(K/F)`, flat outside the quoted strikes.
+    pub fn implied_vol_at_moneyness(&self, forward: f64, moneyness: f64) -> Option<f64> {
+        let k = |strike: f64| (strike / forward).ln();
+        let (first, last) = (self.points.first()?, self.points.last()?);
+        if moneyness <= k(first.0) {
+            return Some(first.1);
+        }
+        if moneyness >= k(last.0) {
+            return Some(last.1);
+        }
+        let window = self.points.windows(2).find(|w| moneyness <= k(w[1].0))?;
+        let ((k1, v1), (k2, v2)) = ((k(window[0].0), window[0].1), (k(window[1].0), window[1].1));
+        Some(v1 + (v2 - v1) * (moneyness - k1) / (k2 - k1))
+    }
+}
+
+/// An implied volatility surface of one underlying, e.g. `BTC` or `SOL_USDC`, built from
+/// `markprice.options` batches and option tickers.
+///
+/// Strikes are interpolated linearly in log-moneyness within each expiry,
+/// and expiries linearly in total variance at constant moneyness.
+/// Volatilities are fractions, like in `MarkPriceOptionData::iv`.
+///
+/// A strike quoted by both a call and a put takes the volatility of the out of the money
+/// one: the put below the forward, the call at and above it or when the forward is unknown.
+#[derive(Debug, Clone)]
+pub struct VolSurface {
+    underlying: String,
+    slices: BTreeMap<DateTime<Utc>, VolSlice>,
+    index_price: Option<f64>,
+}
+
+impl VolSurface {
+    pub fn new(underlying: &str) -> Self {
+        Self {
+            underlying: underlying.to_string(),
+            slices: BTreeMap::new(),
+            index_price: None,
+        }
+    }
+
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    /// The price used as forward for expiries that have not received one.
+    pub fn set_index_price(&mut self, index_price: f64) {
+        self.index_price = Some(index_price);
+    }
+
+    /// Sets the implied volatility of an option, returning whether it was taken. Non option
+    /// instruments and the options of other underlyings are rejected.
+    pub fn insert(&mut self, instrument: &InstrumentName, vol: f64, forward: Option<f64>) -> bool {
+        let (expiry, strike, option_type) = match (
+            instrument.expiry(),
+            instrument.strike(),
+            instrument.option_type(),
+        ) {
+            (Some(expiry), Some(strike), Some(option_type)) => (expiry, strike, option_type),
+            _ => return false,
+        };
+        if instrument.underlying() != self.underlying {
+            return false;
+        }
+        let index_price = self.index_price;
+        let slice = self.slices.entry(expiry).or_default();
+        if forward.is_some() {
+            slice.forward = forward;
+        }
+        let forward = slice.forward.or(index_price);
+        slice.insert(strike, vol, option_type, forward);
+        true
+    }
+
+    pub fn update_mark_prices(&mut self, data: &[MarkPriceOptionData]) {
+        for item in data {
+            if let Ok(instrument) = item.instrument_name.parse() {
+                let forward = item.synthetic_future.as_ref().map(|f| f.mark_price);
+                self.insert(&instrument, item.iv, forward);
+            }
+        }
+    }
+
+    /// Returns whether the ticker was taken, see `insert`.
+    pub fn update_ticker(&mut self, ticker: &TickerData) -> bool {
+        let instrument = match ticker.instrument_name.parse() {
+            Ok(instrument) => instrument,
+            Err(_) => return false,
+        };
+        match ticker.mark_iv {
+            Some(mark_iv) => self.insert(&instrument, mark_iv / 100., ticker.underlying_price),
+            None => false,
+        }
+    }
+
+    /// Drops the slices that expired before `now`.
+    pub fn remove_expired(&mut self, now: DateTime<Utc>) {
+        self.slices = self.slices.split_off(&now);
+    }
+
+    pub fn expiries(&self) -> impl Iterator<Item = &DateTime<Utc>> {
+        self.slices.keys()
+    }
+
+    pub fn slice(&self, expiry: DateTime<Utc>) -> Option<&VolSlice> {
+        self.slices.get(&expiry)
+    }
+
+    // Live slices with their time to expiry and forward
+    fn nodes(&self, now: DateTime<Utc>) -> Vec<(f64, f64, &VolSlice)> {
+        self.slices
+            .iter()
+            .filter_map(|(expiry, slice)| {
+                let time = time_to_expiry(*expiry, now);
+                let forward = slice.forward.or(self.index_price)?;
+                if time > 0. && !slice.points.is_empty() {
+                    Some((time, forward, slice))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The forward at `expiry`, interpolated linearly in time between the known expiries.
+    pub fn forward(&self, expiry: DateTime<Utc>, now: DateTime<Utc>) -> Option<f64> {
+        let time = time_to_expiry(expiry, now);
+        let nodes = self.nodes(now);
+        let (first, last) = (nodes.first()?, nodes.last()?);
+        if time <= first.0 {
+            return Some(first.1);
+        }
+        if time >= last.0 {
+            return Some(last.1);
+        }
+        let window = nodes.windows(2).find(|w| time <= w[1].0)?;
+        let ((t1, f1, _), (t2, f2, _)) = (window[0], window[1]);
+        Some(f1 + (f2 - f1) * (time - t1) / (t2 - t1))
+    }
+
+    /// The implied volatility for an arbitrary strike and expiry.
+    pub fn implied_vol(
+        &self,
+        strike: f64,
+        expiry: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<f64> {
+        let forward = self.forward(expiry, now)?;
+        self.implied_vol_at_moneyness((strike / forward).ln(), expiry, now)
+    }
+
+    /// The implied volatility at log-moneyness `ln(K/F)` for an arbitrary expiry.
+    pub fn implied_vol_at_moneyness(
+        &self,
+        moneyness: f64,
+        expiry: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<f64> {
+        let time = time_to_expiry(expiry, now);
+        let nodes = self.nodes(now);
+        let vol_at = |(_, forward, slice): (f64, f64, &VolSlice)| {
+            slice.implied_vol_at_moneyness(forward, moneyness)
+        };
+        let (first, last) = (*nodes.first()?, *nodes.last()?);
+        if time <= first.0 {
+            return vol_at(first);
+        }
+        if time >= last.0 {
+            return vol_at(last);
+        }
+        let window = nodes.windows(2).find(|w| time <= w[1].0)?;
+        let (t1, t2) = (window[0].0, window[1].0);
+        let w1 = vol_at(window[0])?.powi(2) * t1;
+        let w2 = vol_at(window[1])?.powi(2) * t2;
+        let variance = w1 + (w2 - w1) * (time - t1) / (t2 - t1);
+        Some((variance.max(0.) / time).sqrt())
+    }
+}
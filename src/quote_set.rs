@@ -0,0 +1,189 @@
+use crate::models::{
+    CancelQuotesRequest, MassQuoteItem, MassQuoteLevel, MassQuoteRequest, MassQuoteResponse,
+    QuoteSide,
+};
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+/// A two sided quote, either side can be left out.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Quote {
+    pub bid: Option<(f64, f64)>,
+    pub ask: Option<(f64, f64)>,
+}
+
+impl Quote {
+    pub fn new(bid: (f64, f64), ask: (f64, f64)) -> Self {
+        Self {
+            bid: Some(bid),
+            ask: Some(ask),
+        }
+    }
+
+    fn side(&self, side: QuoteSide) -> Option<(f64, f64)> {
+        match side {
+            QuoteSide::Bid => self.bid,
+            QuoteSide::Ask => self.ask,
+        }
+    }
+
+    fn side_mut(&mut self, side: QuoteSide) -> &mut Option<(f64, f64)> {
+        match side {
+            QuoteSide::Bid => &mut self.bid,
+            QuoteSide::Ask => &mut self.ask,
+        }
+    }
+}
+
+/// The quotes of one quote set, tracking what is live on the exchange so that updating
+/// to a new set of desired quotes only sends the instruments and sides that changed.
+///
+/// The quotes sent by `update` are pending under the quote id of their request until
+/// `on_response` confirms them. When the request fails or times out, `on_failure` drops
+/// them and the next update resends them. Several requests can be in flight, a response
+/// only applies the quotes of its own request.
+#[derive(Debug, Clone)]
+pub struct QuoteSet {
+    quote_set_id: String,
+    mmp_group: String,
+    live: BTreeMap<String, Quote>,
+    /// The desired quotes of the requests in flight, by their sequence number
+    pending: BTreeMap<u64, BTreeMap<String, Quote>>,
+    /// Suffix of the quote ids, seeded with the creation time so that ids are not reused
+    /// across restarts
+    seq: u64,
+}
+
+impl QuoteSet {
+    pub fn new(quote_set_id: &str, mmp_group: &str) -> Self {
+        Self {
+            quote_set_id: quote_set_id.to_string(),
+            mmp_group: mmp_group.to_string(),
+            live: BTreeMap::new(),
+            pending: BTreeMap::new(),
+            seq: Utc::now().timestamp_millis() as u64,
+        }
+    }
+
+    pub fn quote_set_id(&self) -> &str {
+        &self.quote_set_id
+    }
+
+    pub fn live(&self) -> &BTreeMap<String, Quote> {
+        &self.live
+    }
+
+    /// The quotes sent by the request `quote_id`, if it is not confirmed yet.
+    pub fn pending(&self, quote_id: &str) -> Option<&BTreeMap<String, Quote>> {
+        self.pending.get(&self.seq_of(quote_id)?)
+    }
+
+    /// The minimal changes turning the live quotes, and the ones of the requests in flight,
+    /// into `desired`: changed sides are requoted, and sides no longer desired are removed
+    /// with a zero amount.
+    pub fn diff(&self, desired: &BTreeMap<String, Quote>) -> Vec<MassQuoteItem> {
+        let known: Vec<_> = std::iter::once(&self.live)
+            .chain(self.pending.values())
+            .collect();
+        let mut instruments: Vec<_> = desired
+            .keys()
+            .chain(known.iter().flat_map(|quotes| quotes.keys()))
+            .collect();
+        instruments.sort();
+        instruments.dedup();
+
+        instruments
+            .into_iter()
+            .filter_map(|instrument| {
+                let quote = desired.get(instrument).copied().unwrap_or_default();
+                let level = |side| {
+                    let sides: Vec<_> = known
+                        .iter()
+                        .map(|quotes| quotes.get(instrument).and_then(|q| q.side(side)))
+                        .collect();
+                    let wanted = quote.side(side);
+                    if sides.iter().all(|current| *current == wanted) {
+                        return None;
+                    }
+                    match wanted {
+                        Some((price, amount)) => Some(MassQuoteLevel::new(price, amount)),
+                        None => {
+                            let (price, _) = sides.iter().rev().flatten().next()?;
+                            Some(MassQuoteLevel::new(*price, 0.))
+                        }
+                    }
+                };
+                let (bid, ask) = (level(QuoteSide::Bid), level(QuoteSide::Ask));
+                if bid.is_none() && ask.is_none() {
+                    return None;
+                }
+                Some(MassQuoteItem {
+                    instrument_name: instrument.clone(),
+                    quote_set_id: Some(self.quote_set_id.clone()),
+                    bid,
+                    ask,
+                })
+            })
+            .collect()
+    }
+
+    /// Builds the mass quote request moving to `desired`, or `None` if nothing changed.
+    /// The desired quotes stay pending until `on_response`.
+    pub fn update(&mut self, desired: BTreeMap<String, Quote>) -> Option<MassQuoteRequest> {
+        let quotes = self.diff(&desired);
+        if quotes.is_empty() {
+            return None;
+        }
+        self.seq += 1;
+        self.pending.insert(
+            self.seq,
+            desired
+                .into_iter()
+                .filter(|(_, quote)| quote.bid.is_some() || quote.ask.is_some())
+                .collect(),
+        );
+        let quote_id = format!("{}-{}", self.quote_set_id, self.seq);
+        Some(MassQuoteRequest::new(&quote_id, &self.mmp_group, quotes))
+    }
+
+    /// Makes the quotes of the request `quote_id` live, but the sides rejected by the
+    /// exchange so that the next update resends them. The requests sent before it are
+    /// superseded, and responses of unknown requests are ignored.
+    pub fn on_response(&mut self, quote_id: &str, response: &MassQuoteResponse) {
+        let seq = match self.seq_of(quote_id) {
+            Some(seq) if self.pending.contains_key(&seq) => seq,
+            _ => return,
+        };
+        let newer = self.pending.split_off(&(seq + 1));
+        self.live = std::mem::replace(&mut self.pending, newer)
+            .remove(&seq)
+            .unwrap_or_default();
+        for error in &response.errors {
+            if let Some(quote) = self.live.get_mut(&error.instrument_name) {
+                *quote.side_mut(error.side) = None;
+            }
+        }
+        self.live
+            .retain(|_, quote| quote.bid.is_some() || quote.ask.is_some());
+    }
+
+    /// Drops the quotes of the request `quote_id` after it failed, the live quotes are
+    /// unchanged.
+    pub fn on_failure(&mut self, quote_id: &str) {
+        if let Some(seq) = self.seq_of(quote_id) {
+            self.pending.remove(&seq);
+        }
+    }
+
+    /// Pulls every quote of the set, e.g. after MMP fired.
+    pub fn cancel(&mut self) -> CancelQuotesRequest {
+        self.live.clear();
+        self.pending.clear();
+        CancelQuotesRequest::by_quote_set_id(&self.quote_set_id)
+    }
+
+    fn seq_of(&self, quote_id: &str) -> Option<u64> {
+        let seq = quote_id.strip_prefix(self.quote_set_id.as_str())?;
+        seq.strip_prefix('-')?.parse().ok()
+    }
+}
@@ -0,0 +1,321 @@
+use crate::models::{
+    Currency, GetSubaccountsRequest, GetSubaccountsResponse, GetTransfersRequest,
+    SubmitTransferBetweenSubaccountsRequest, TransferDirection, TransferItem, TransferState,
+    TransferType,
+};
+use crate::DeribitAPIClient;
+use chrono::{DateTime, Utc};
+use failure::Error;
+use fehler::throws;
+use futures::TryStreamExt;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// How far the clock of Deribit may be behind ours when looking up a transfer in flight
+const CLOCK_SKEW_MS: i64 = 5 * 60 * 1000;
+
+/// Bounds of the margin ratio, `initial_margin / equity`, of an account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginBand {
+    pub min: f64,
+    pub target: f64,
+    pub max: f64,
+    /// Smaller transfers are not worth sending
+    pub min_transfer: f64,
+}
+
+impl MarginBand {
+    pub fn new(min: f64, target: f64, max: f64) -> Self {
+        Self {
+            min,
+            target,
+            max,
+            min_transfer: 0.,
+        }
+    }
+
+    pub fn with_min_transfer(self, min_transfer: f64) -> Self {
+        Self {
+            min_transfer,
+            ..self
+        }
+    }
+}
+
+/// A transfer of the rebalancing, its id is the same every time the same round is planned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedTransfer {
+    pub id: String,
+    pub currency: Currency,
+    pub source: u64,
+    pub destination: u64,
+    pub amount: f64,
+}
+
+/// A transfer sent without a response, it may or may not have happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InFlightTransfer {
+    pub attempted_at: DateTime<Utc>,
+    pub amount: f64,
+    pub source: u64,
+    pub destination: u64,
+}
+
+impl InFlightTransfer {
+    /// Whether a transfer listed for the account `listed_for` may be this one: the same
+    /// amount paid by the source to the destination, created since the attempt give or
+    /// take the clock skew.
+    pub fn matches(&self, item: &TransferItem, listed_for: u64, destination_name: &str) -> bool {
+        let since = self.attempted_at.timestamp_millis() - CLOCK_SKEW_MS;
+        self.source == listed_for
+            && item.created_timestamp as i64 >= since
+            && (item.amount - self.amount).abs() < 1e-12
+            && item.r#type == TransferType::Subaccount
+            && item.direction == TransferDirection::Payment
+            && item.state != TransferState::Cancelled
+            && item.other_side == destination_name
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum JournalEntry {
+    InFlight(InFlightTransfer),
+    Done { transfer_id: i64 },
+}
+
+/// Keeps the margin ratio of every account within its band by moving funds from the
+/// accounts under the band to the ones over it.
+///
+/// Transfers are journaled by id so that retrying a round, e.g. after a timeout, does not
+/// send them twice: sent transfers are skipped, and a transfer whose outcome is unknown
+/// is looked up in the transfers of its source before being sent again. Only the
+/// transfers of the authenticated main account can be listed, so it is the only source.
+#[derive(Debug, Clone, Default)]
+pub struct SubaccountRebalancer {
+    bands: HashMap<Currency, MarginBand>,
+    journal: HashMap<String, JournalEntry>,
+}
+
+impl SubaccountRebalancer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_band(&mut self, currency: Currency, band: MarginBand) {
+        self.bands.insert(currency, band);
+    }
+
+    /// The transfers bringing the accounts out of their band back to the target ratio, the
+    /// largest needs being served first by the surplus of the main account.
+    pub fn plan(&self, round: &str, accounts: &[GetSubaccountsResponse]) -> Vec<PlannedTransfer> {
+        let mut currencies: Vec<_> = self.bands.iter().collect();
+        currencies.sort_by_key(|(currency, _)| *currency);
+
+        let mut transfers = vec![];
+        for (currency, band) in currencies {
+            let mut deficits = vec![];
+            let mut surpluses = vec![];
+            for account in accounts {
+                let portfolio = match account.portfolio.get(currency) {
+                    Some(portfolio) => portfolio,
+                    None => continue,
+                };
+                let wanted_equity = portfolio.initial_margin / band.target;
+                let ratio = portfolio.initial_margin / portfolio.equity;
+                if portfolio.equity <= 0. || ratio > band.max {
+                    deficits.push((account.id, wanted_equity - portfolio.equity));
+                } else if ratio < band.min && account.r#type == "main" {
+                    let spare = (portfolio.equity - wanted_equity)
+                        .min(portfolio.available_withdrawal_funds);
+                    surpluses.push((account.id, spare));
+                }
+            }
+            let by_amount = |a: &(u64, f64), b: &(u64, f64)| {
+                b.1.partial_cmp(&a.1)
+                    .unwrap_or(Ordering::Equal)
+                    .then(a.0.cmp(&b.0))
+            };
+            deficits.sort_by(by_amount);
+            surpluses.sort_by(by_amount);
+
+            let mut surpluses = surpluses.into_iter().peekable();
+            for (destination, mut need) in deficits {
+                while let Some((source, spare)) = surpluses.peek_mut() {
+                    let amount = need.min(*spare);
+                    if amount < band.min_transfer || amount <= 0. {
+                        break;
+                    }
+                    transfers.push(PlannedTransfer {
+                        id: format!("{}:{}:{}:{}", round, currency, source, destination),
+                        currency: currency.clone(),
+                        source: *source,
+                        destination,
+                        amount,
+                    });
+                    need -= amount;
+                    *spare -= amount;
+                    if *spare < band.min_transfer || *spare <= 0. {
+                        surpluses.next();
+                    }
+                    if need < band.min_transfer || need <= 0. {
+                        break;
+                    }
+                }
+            }
+        }
+        transfers
+    }
+
+    pub fn is_done(&self, transfer: &PlannedTransfer) -> bool {
+        matches!(
+            self.journal.get(&transfer.id),
+            Some(JournalEntry::Done { .. })
+        )
+    }
+
+    pub fn in_flight(&self, transfer: &PlannedTransfer) -> Option<&InFlightTransfer> {
+        match self.journal.get(&transfer.id) {
+            Some(JournalEntry::InFlight(in_flight)) => Some(in_flight),
+            _ => None,
+        }
+    }
+
+    /// Journals a transfer as in flight, `execute` does so right before sending it.
+    pub fn begin(&mut self, transfer: &PlannedTransfer, now: DateTime<Utc>) {
+        self.journal.insert(
+            transfer.id.clone(),
+            JournalEntry::InFlight(InFlightTransfer {
+                attempted_at: now,
+                amount: transfer.amount,
+                source: transfer.source,
+                destination: transfer.destination,
+            }),
+        );
+    }
+
+    /// Marks a transfer in flight as done if it is among the transfers listed for the
+    /// account `listed_for` and not claimed by another journaled transfer, returning whether
+    /// it was found.
+    pub fn recover(
+        &mut self,
+        transfer: &PlannedTransfer,
+        listed_for: u64,
+        destination_name: &str,
+        listed: &[TransferItem],
+    ) -> bool {
+        let in_flight = match self.in_flight(transfer) {
+            Some(in_flight) => in_flight,
+            None => return false,
+        };
+        let claimed: HashSet<_> = self
+            .journal
+            .values()
+            .filter_map(|entry| match entry {
+                JournalEntry::Done { transfer_id } => Some(*transfer_id),
+                JournalEntry::InFlight(_) => None,
+            })
+            .collect();
+        let found = listed.iter().find(|item| {
+            in_flight.matches(item, listed_for, destination_name) && !claimed.contains(&item.id)
+        });
+        match found {
+            Some(item) => {
+                self.journal.insert(
+                    transfer.id.clone(),
+                    JournalEntry::Done {
+                        transfer_id: item.id,
+                    },
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sends a planned transfer unless it was already, returning whether it was sent now.
+    ///
+    /// A transfer in flight which cannot be looked up, its source not being the main
+    /// account, is left in flight rather than risking to send it twice.
+    #[throws(Error)]
+    pub async fn execute(
+        &mut self,
+        client: &mut DeribitAPIClient,
+        transfer: &PlannedTransfer,
+        now: DateTime<Utc>,
+    ) -> bool {
+        match self.journal.get(&transfer.id) {
+            Some(JournalEntry::Done { .. }) => return false,
+            Some(JournalEntry::InFlight(in_flight)) => {
+                let in_flight = in_flight.clone();
+                let accounts = client.call(GetSubaccountsRequest::new()).await?.await?;
+                let main = accounts.iter().find(|account| account.r#type == "main");
+                let main = match main {
+                    Some(main) if main.id == in_flight.source => main.id,
+                    _ => return false,
+                };
+                let destination_name = accounts
+                    .iter()
+                    .find(|account| account.id == in_flight.destination)
+                    .map(|account| account.username.clone())
+                    .unwrap_or_default();
+                let listed = listed_since(client, &transfer.currency, &in_flight).await?;
+                if self.recover(transfer, main, &destination_name, &listed) {
+                    return false;
+                }
+            }
+            None => {}
+        }
+
+        self.begin(transfer, now);
+        let req = SubmitTransferBetweenSubaccountsRequest::new(
+            transfer.currency.clone(),
+            transfer.amount,
+            transfer.source,
+            transfer.destination,
+        );
+        let sent = client.call(req).await?.await?;
+        self.journal.insert(
+            transfer.id.clone(),
+            JournalEntry::Done {
+                transfer_id: sent.id,
+            },
+        );
+        true
+    }
+
+    /// Fetches the accounts, plans the round and executes it, returning the transfers sent.
+    #[throws(Error)]
+    pub async fn rebalance(
+        &mut self,
+        client: &mut DeribitAPIClient,
+        round: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<PlannedTransfer> {
+        let accounts = client
+            .call(GetSubaccountsRequest::with_portfolio())
+            .await?
+            .await?;
+        let mut sent = vec![];
+        for transfer in self.plan(round, &accounts) {
+            if self.execute(client, &transfer, now).await? {
+                sent.push(transfer);
+            }
+        }
+        sent
+    }
+}
+
+// All the pages of transfers of the authenticated account which may be the one in flight
+#[throws(Error)]
+async fn listed_since(
+    client: &mut DeribitAPIClient,
+    currency: &Currency,
+    in_flight: &InFlightTransfer,
+) -> Vec<TransferItem> {
+    let since = in_flight.attempted_at.timestamp_millis() - CLOCK_SKEW_MS;
+    client
+        .call_paginated(GetTransfersRequest::with_currency(currency.clone()))
+        .try_filter(|item| futures::future::ready(item.created_timestamp as i64 >= since))
+        .try_collect()
+        .await?
+}
@@ -0,0 +1,82 @@
+//! Account risk metrics computed from the account summary and the positions.
+
+use crate::models::{Direction, GetAccountSummaryResponse, GetPositionsResponse, InstrumentName};
+use crate::pricing::OptionGreeks;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// The share of the margin balance used by the margin requirements, the account being
+/// liquidated once `maintenance` reaches 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginUtilization {
+    pub initial: f64,
+    pub maintenance: f64,
+}
+
+impl MarginUtilization {
+    /// `None` without a margin balance, e.g. for an empty account.
+    pub fn new(summary: &GetAccountSummaryResponse) -> Option<Self> {
+        if summary.margin_balance <= 0. {
+            return None;
+        }
+        Some(Self {
+            initial: summary.initial_margin / summary.margin_balance,
+            maintenance: summary.maintenance_margin / summary.margin_balance,
+        })
+    }
+
+    /// The utilization the portfolio would have, from a `SimulatePortfolioRequest` response.
+    pub fn projected(summary: &GetAccountSummaryResponse) -> Option<Self> {
+        let current = Self::new(summary)?;
+        Some(Self {
+            initial: summary
+                .projected_initial_margin
+                .map_or(current.initial, |im| im / summary.margin_balance),
+            maintenance: summary
+                .projected_maintenance_margin
+                .map_or(current.maintenance, |mm| mm / summary.margin_balance),
+        })
+    }
+}
+
+/// How far the mark price can move against the position before it gets liquidated, as a
+/// fraction of the mark price. `None` for flat positions or without a liquidation price.
+pub fn liquidation_distance(position: &GetPositionsResponse) -> Option<f64> {
+    let liquidation_price = position.estimated_liquidation_price?;
+    let distance = (position.mark_price - liquidation_price) / position.mark_price;
+    match position.direction {
+        Direction::Buy => Some(distance),
+        Direction::Sell => Some(-distance),
+        Direction::Zero => None,
+    }
+}
+
+/// The positions closest to liquidation first, skipping the ones without a distance.
+pub fn closest_to_liquidation(positions: &[GetPositionsResponse]) -> Vec<(&str, f64)> {
+    let mut distances: Vec<_> = positions
+        .iter()
+        .filter_map(|p| Some((p.instrument_name.as_str(), liquidation_distance(p)?)))
+        .collect();
+    distances.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+    distances
+}
+
+/// The greeks of the positions summed by underlying, e.g. `BTC` or `ETH_USDC`.
+///
+/// `delta` covers every position, the other greeks only come from options. The values are
+/// the position greeks of `GetPositionsResponse`, already scaled by the position size.
+pub fn greeks_by_underlying(positions: &[GetPositionsResponse]) -> BTreeMap<String, OptionGreeks> {
+    let mut greeks = BTreeMap::<String, OptionGreeks>::new();
+    for position in positions {
+        let underlying = match position.instrument_name.parse::<InstrumentName>() {
+            Ok(instrument) => instrument.underlying().to_string(),
+            Err(_) => position.instrument_name.clone(),
+        };
+        let total = greeks.entry(underlying).or_default();
+        total.delta += position.delta;
+        total.gamma += position.gamma.unwrap_or(0.);
+        total.vega += position.vega.unwrap_or(0.);
+        total.theta += position.theta.unwrap_or(0.);
+    }
+    greeks
+}
@@ -0,0 +1,725 @@
+//! Pre-trade risk limits, checked before any order reaches Deribit.
+
+use crate::models::subscription::{TickerData, UserChangesData, UserOrdersData};
+use crate::models::{
+    BuyRequest, BuyResponse, CancelAllRequest, Currency, Direction, EditRequest, EditResponse,
+    GetOpenOrdersByCurrencyRequest, GetPositionsRequest, GetPositionsResponse, InstrumentName,
+    Order, OrderState, Request, SellRequest, SellResponse, Trade, TradeRequest, TradeResponse,
+};
+use crate::{DeribitAPICallResult, DeribitAPIClient};
+use failure::Error;
+use fehler::throws;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The private methods which cannot trade, the only ones besides the public methods that
+/// `RiskLimitedClient::call` sends. Any other method, e.g. `private/close_position` or
+/// `private/execute_block_trade`, is taken to trade around the risk checks.
+pub const NON_TRADING_METHODS: &[&str] = &[
+    "private/add_to_address_book",
+    "private/cancel",
+    "private/cancel_all",
+    "private/cancel_all_by_currency",
+    "private/cancel_all_by_instrument",
+    "private/cancel_by_label",
+    "private/cancel_quotes",
+    "private/cancel_transfer_by_id",
+    "private/cancel_withdrawal",
+    "private/change_api_key_name",
+    "private/change_scope_in_api_key",
+    "private/change_subaccount_name",
+    "private/create_api_key",
+    "private/create_deposit_address",
+    "private/create_subaccount",
+    "private/disable_api_key",
+    "private/disable_cancel_on_disconnect",
+    "private/enable_api_key",
+    "private/enable_cancel_on_disconnect",
+    "private/get_account_summary",
+    "private/get_address_book",
+    "private/get_block_trade",
+    "private/get_cancel_on_disconnect",
+    "private/get_current_deposit_address",
+    "private/get_deposits",
+    "private/get_last_block_trades_by_currency",
+    "private/get_margins",
+    "private/get_mmp_config",
+    "private/get_new_announcements",
+    "private/get_open_orders_by_currency",
+    "private/get_open_orders_by_instrument",
+    "private/get_open_orders_by_label",
+    "private/get_order_history_by_currency",
+    "private/get_order_history_by_instrument",
+    "private/get_order_margin_by_ids",
+    "private/get_order_state",
+    "private/get_positions",
+    "private/get_settlement_history_by_currency",
+    "private/get_settlement_history_by_instrument",
+    "private/get_stop_order_history",
+    "private/get_subaccounts",
+    "private/get_transfers",
+    "private/get_user_trades_by_currency",
+    "private/get_user_trades_by_currency_and_time",
+    "private/get_user_trades_by_instrument",
+    "private/get_user_trades_by_instrument_and_time",
+    "private/get_user_trades_by_order",
+    "private/get_withdrawals",
+    "private/invalidate_block_trade_signature",
+    "private/list_api_keys",
+    "private/logout",
+    "private/remove_api_key",
+    "private/remove_from_address_book",
+    "private/reset_api_key",
+    "private/reset_mmp",
+    "private/set_announcement_as_read",
+    "private/set_api_key_as_default",
+    "private/set_email_for_subaccount",
+    "private/set_mmp_config",
+    "private/set_password_for_subaccount",
+    "private/simulate_portfolio",
+    "private/submit_transfer_between_subaccounts",
+    "private/submit_transfer_to_subaccount",
+    "private/submit_transfer_to_user",
+    "private/subscribe",
+    "private/toggle_notifications_from_subaccount",
+    "private/toggle_subaccount_login",
+    "private/unsubscribe",
+    "private/withdraw",
+];
+
+/// Limits of a single instrument, in the units of the order amount.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct InstrumentLimits {
+    pub max_order_size: Option<f64>,
+    /// Largest absolute position, counting the open orders as filled
+    pub max_position: Option<f64>,
+}
+
+/// The limits enforced by the `RiskChecker`, unset limits are not checked.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RiskLimits {
+    /// The limits of the instruments without their own
+    pub default: InstrumentLimits,
+    pub instruments: HashMap<String, InstrumentLimits>,
+    /// Largest USD notional of a single order
+    pub max_notional: Option<f64>,
+    /// Largest number of open orders across all instruments
+    pub max_open_orders: Option<usize>,
+    /// Largest distance of a limit price from the mark price, as a fraction of it
+    pub price_band: Option<f64>,
+}
+
+impl RiskLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_default(self, default: InstrumentLimits) -> Self {
+        Self { default, ..self }
+    }
+
+    pub fn with_instrument(mut self, instrument_name: &str, limits: InstrumentLimits) -> Self {
+        self.instruments.insert(instrument_name.to_string(), limits);
+        self
+    }
+
+    pub fn with_max_notional(self, max_notional: f64) -> Self {
+        Self {
+            max_notional: Some(max_notional),
+            ..self
+        }
+    }
+
+    pub fn with_max_open_orders(self, max_open_orders: usize) -> Self {
+        Self {
+            max_open_orders: Some(max_open_orders),
+            ..self
+        }
+    }
+
+    pub fn with_price_band(self, price_band: f64) -> Self {
+        Self {
+            price_band: Some(price_band),
+            ..self
+        }
+    }
+
+    pub fn instrument(&self, instrument_name: &str) -> InstrumentLimits {
+        self.instruments
+            .get(instrument_name)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Why an order was refused before being sent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskViolation {
+    KillSwitchEngaged,
+    /// A method which may trade called around the checks, see `NON_TRADING_METHODS`
+    UncheckedOrder(String),
+    UnknownOrder(String),
+    /// The notional or the price band cannot be checked without a ticker
+    NoMarkPrice(String),
+    OrderSizeExceeded {
+        instrument_name: String,
+        amount: f64,
+        limit: f64,
+    },
+    NotionalExceeded {
+        instrument_name: String,
+        notional: f64,
+        limit: f64,
+    },
+    TooManyOpenOrders {
+        limit: usize,
+    },
+    PositionExceeded {
+        instrument_name: String,
+        position: f64,
+        limit: f64,
+    },
+    PriceOutOfBand {
+        instrument_name: String,
+        price: f64,
+        mark_price: f64,
+        band: f64,
+    },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RiskViolation::KillSwitchEngaged => write!(fmt, "kill switch engaged"),
+            RiskViolation::UncheckedOrder(method) => {
+                write!(fmt, "{} must go through the risk checks", method)
+            }
+            RiskViolation::UnknownOrder(order_id) => write!(fmt, "unknown order {}", order_id),
+            RiskViolation::NoMarkPrice(instrument_name) => {
+                write!(fmt, "no mark price for {}", instrument_name)
+            }
+            RiskViolation::OrderSizeExceeded {
+                instrument_name,
+                amount,
+                limit,
+            } => write!(
+                fmt,
+                "{} order of {} exceeds the size limit of {}",
+                instrument_name, amount, limit
+            ),
+            RiskViolation::NotionalExceeded {
+                instrument_name,
+                notional,
+                limit,
+            } => write!(
+                fmt,
+                "{} order notional of {} USD exceeds the limit of {}",
+                instrument_name, notional, limit
+            ),
+            RiskViolation::TooManyOpenOrders { limit } => {
+                write!(fmt, "more than {} open orders", limit)
+            }
+            RiskViolation::PositionExceeded {
+                instrument_name,
+                position,
+                limit,
+            } => write!(
+                fmt,
+                "{} position of {} exceeds the limit of {}",
+                instrument_name, position, limit
+            ),
+            RiskViolation::PriceOutOfBand {
+                instrument_name,
+                price,
+                mark_price,
+                band,
+            } => write!(
+                fmt,
+                "{} price {} is more than {} away from the mark price {}",
+                instrument_name, price, band, mark_price
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RiskViolation {}
+
+#[derive(Debug, Clone, Default)]
+struct Controls {
+    limits: RiskLimits,
+    killed: bool,
+}
+
+/// The limits and the kill switch of a `RiskChecker`, shared with the checker so that
+/// they can be held apart from the strategy sending the orders.
+#[derive(Debug, Clone, Default)]
+pub struct RiskControl {
+    controls: Arc<Mutex<Controls>>,
+}
+
+impl RiskControl {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            controls: Arc::new(Mutex::new(Controls {
+                limits,
+                killed: false,
+            })),
+        }
+    }
+
+    pub fn limits(&self) -> RiskLimits {
+        self.get().limits.clone()
+    }
+
+    pub fn set_limits(&self, limits: RiskLimits) {
+        self.get().limits = limits;
+    }
+
+    /// Rejects every order until `reset_kill_switch`.
+    pub fn kill(&self) {
+        self.get().killed = true;
+    }
+
+    pub fn reset_kill_switch(&self) {
+        self.get().killed = false;
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.get().killed
+    }
+
+    fn get(&self) -> MutexGuard<'_, Controls> {
+        // Nothing panics while the lock is held
+        self.controls.lock().unwrap()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Mark {
+    mark_price: f64,
+    index_price: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct OpenOrder {
+    instrument_name: String,
+    direction: Direction,
+    /// The amount left to fill
+    amount: f64,
+    reduce_only: bool,
+}
+
+/// The state the limits are checked against: the mark prices, the positions and the
+/// open orders, fed from the tickers, the `user.changes` notifications and the positions.
+///
+/// Only `on_user_changes` and `on_position` move the positions besides the fills of the
+/// orders sent through the `RiskLimitedClient`: the `user.orders` channel has no fills,
+/// so an order leaving the book there would leave its fills out of the position. The open
+/// orders count as filled when checking the position limits, except the reduce only ones.
+///
+/// Not `Clone`, a copy would share the limits and the kill switch of the checker.
+#[derive(Debug, Default)]
+pub struct RiskChecker {
+    control: RiskControl,
+    marks: HashMap<String, Mark>,
+    positions: HashMap<String, f64>,
+    open_orders: HashMap<String, OpenOrder>,
+}
+
+impl RiskChecker {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self::with_control(RiskControl::new(limits))
+    }
+
+    /// A checker whose limits and kill switch are set through `control`.
+    pub fn with_control(control: RiskControl) -> Self {
+        Self {
+            control,
+            ..Self::default()
+        }
+    }
+
+    pub fn limits(&self) -> RiskLimits {
+        self.control.limits()
+    }
+
+    pub fn set_limits(&mut self, limits: RiskLimits) {
+        self.control.set_limits(limits);
+    }
+
+    /// Rejects every order until `reset_kill_switch`.
+    pub fn kill(&mut self) {
+        self.control.kill();
+    }
+
+    pub fn reset_kill_switch(&mut self) {
+        self.control.reset_kill_switch();
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.control.is_killed()
+    }
+
+    /// The signed position of an instrument, negative when short.
+    pub fn position(&self, instrument_name: &str) -> f64 {
+        self.positions.get(instrument_name).copied().unwrap_or(0.)
+    }
+
+    pub fn open_orders(&self) -> usize {
+        self.open_orders.len()
+    }
+
+    pub fn on_ticker(&mut self, ticker: &TickerData) {
+        self.marks.insert(
+            ticker.instrument_name.clone(),
+            Mark {
+                mark_price: ticker.mark_price,
+                index_price: ticker.index_price,
+            },
+        );
+    }
+
+    pub fn on_position(&mut self, position: &GetPositionsResponse) {
+        self.positions
+            .insert(position.instrument_name.clone(), position.size);
+    }
+
+    fn on_order(&mut self, order: &Order) {
+        self.update_order(
+            &order.order_id,
+            order.order_state,
+            OpenOrder {
+                instrument_name: order.instrument_name.clone(),
+                direction: order.direction,
+                amount: order.amount - order.filled_amount.unwrap_or_default(),
+                reduce_only: order.reduce_only,
+            },
+        )
+    }
+
+    fn on_user_order(&mut self, order: &UserOrdersData) {
+        self.update_order(
+            &order.order_id,
+            order.order_state,
+            OpenOrder {
+                instrument_name: order.instrument_name.clone(),
+                direction: order.direction,
+                amount: order.amount - order.filled_amount,
+                reduce_only: order.reduce_only,
+            },
+        )
+    }
+
+    /// Applies an update from the `user.changes` channel, its positions replacing the
+    /// tracked ones.
+    pub fn on_user_changes(&mut self, changes: &UserChangesData) {
+        for order in &changes.orders {
+            self.on_user_order(order);
+        }
+        for position in &changes.positions {
+            self.on_position(position);
+        }
+    }
+
+    fn on_trade(&mut self, trade: &Trade) {
+        *self
+            .positions
+            .entry(trade.instrument_name.clone())
+            .or_default() += signed(trade.direction, trade.amount);
+    }
+
+    /// Refuses the methods which may trade, they only go through the checked ones.
+    pub fn check_call<R: Request>(&self) -> Result<(), RiskViolation> {
+        if R::METHOD.starts_with("public/") || NON_TRADING_METHODS.contains(&R::METHOD) {
+            Ok(())
+        } else {
+            Err(RiskViolation::UncheckedOrder(R::METHOD.to_string()))
+        }
+    }
+
+    pub fn check_buy(&self, req: &BuyRequest) -> Result<(), RiskViolation> {
+        self.check_order(Direction::Buy, req)
+    }
+
+    pub fn check_sell(&self, req: &SellRequest) -> Result<(), RiskViolation> {
+        self.check_order(Direction::Sell, req)
+    }
+
+    /// Checks the edited order as if it replaced the open one.
+    pub fn check_edit(&self, req: &EditRequest) -> Result<(), RiskViolation> {
+        let order = self
+            .open_orders
+            .get(&req.order_id)
+            .ok_or_else(|| RiskViolation::UnknownOrder(req.order_id.clone()))?;
+        let reduce_only = req.reduce_only.unwrap_or(order.reduce_only);
+        self.check(
+            &order.instrument_name,
+            order.direction,
+            req.amount,
+            Some(req.price),
+            reduce_only,
+            Some(&req.order_id),
+        )
+    }
+
+    fn check_order(&self, direction: Direction, req: &TradeRequest) -> Result<(), RiskViolation> {
+        self.check(
+            &req.instrument_name,
+            direction,
+            req.amount,
+            req.price,
+            req.reduce_only,
+            None,
+        )
+    }
+
+    fn check(
+        &self,
+        instrument_name: &str,
+        direction: Direction,
+        amount: f64,
+        price: Option<f64>,
+        reduce_only: bool,
+        replaces: Option<&str>,
+    ) -> Result<(), RiskViolation> {
+        let control = self.control.get();
+        if control.killed {
+            return Err(RiskViolation::KillSwitchEngaged);
+        }
+        let limits = &control.limits;
+
+        // An edit does not add an order
+        if let (Some(limit), None) = (limits.max_open_orders, replaces) {
+            if self.open_orders.len() >= limit {
+                return Err(RiskViolation::TooManyOpenOrders { limit });
+            }
+        }
+
+        let instrument = limits.instrument(instrument_name);
+        if let Some(limit) = instrument.max_order_size {
+            if amount > limit {
+                return Err(RiskViolation::OrderSizeExceeded {
+                    instrument_name: instrument_name.to_string(),
+                    amount,
+                    limit,
+                });
+            }
+        }
+
+        if let Some(limit) = instrument.max_position {
+            if !reduce_only {
+                let position = self.worst_position(instrument_name, direction, replaces)
+                    + signed(direction, amount);
+                if position.abs() > limit {
+                    return Err(RiskViolation::PositionExceeded {
+                        instrument_name: instrument_name.to_string(),
+                        position,
+                        limit,
+                    });
+                }
+            }
+        }
+
+        if limits.max_notional.is_none() && limits.price_band.is_none() {
+            return Ok(());
+        }
+        let mark = self
+            .marks
+            .get(instrument_name)
+            .ok_or_else(|| RiskViolation::NoMarkPrice(instrument_name.to_string()))?;
+
+        if let Some(limit) = limits.max_notional {
+            let notional = notional(instrument_name, amount, mark);
+            if notional > limit {
+                return Err(RiskViolation::NotionalExceeded {
+                    instrument_name: instrument_name.to_string(),
+                    notional,
+                    limit,
+                });
+            }
+        }
+
+        if let (Some(band), Some(price)) = (limits.price_band, price) {
+            if (price - mark.mark_price).abs() > band * mark.mark_price {
+                return Err(RiskViolation::PriceOutOfBand {
+                    instrument_name: instrument_name.to_string(),
+                    price,
+                    mark_price: mark.mark_price,
+                    band,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // The position with the open orders on the same side filled, leaving out the one
+    // being replaced
+    fn worst_position(
+        &self,
+        instrument_name: &str,
+        direction: Direction,
+        replaces: Option<&str>,
+    ) -> f64 {
+        self.open_orders
+            .iter()
+            .filter(|(order_id, order)| {
+                order.instrument_name == instrument_name
+                    && order.direction == direction
+                    && !order.reduce_only
+                    && Some(order_id.as_str()) != replaces
+            })
+            .map(|(_, order)| signed(order.direction, order.amount))
+            .sum::<f64>()
+            + self.position(instrument_name)
+    }
+
+    fn update_order(&mut self, order_id: &str, order_state: OrderState, order: OpenOrder) {
+        match order_state {
+            OrderState::Open | OrderState::Untriggered => {
+                self.open_orders.insert(order_id.to_string(), order);
+            }
+            _ => {
+                self.open_orders.remove(order_id);
+            }
+        }
+    }
+
+    fn on_trade_response(&mut self, resp: &TradeResponse) {
+        for trade in &resp.trades {
+            self.on_trade(trade);
+        }
+        self.on_order(&resp.order);
+    }
+}
+
+/// Feeds a `RiskChecker` from the subscriptions, without access to its limits.
+pub struct RiskFeed<'a> {
+    checker: &'a mut RiskChecker,
+}
+
+impl RiskFeed<'_> {
+    pub fn on_ticker(&mut self, ticker: &TickerData) {
+        self.checker.on_ticker(ticker);
+    }
+
+    pub fn on_position(&mut self, position: &GetPositionsResponse) {
+        self.checker.on_position(position);
+    }
+
+    pub fn on_user_changes(&mut self, changes: &UserChangesData) {
+        self.checker.on_user_changes(changes);
+    }
+}
+
+/// A `DeribitAPIClient` checking every order against a `RiskChecker` before sending it.
+///
+/// Orders only go through `buy`, `sell` and `edit`, `call` refusing any method which may
+/// trade. The limits and the kill switch are only set through the `RiskControl` handed
+/// out by `new`, the client itself can only engage the kill switch, cancelling all the
+/// open orders.
+pub struct RiskLimitedClient {
+    client: DeribitAPIClient,
+    checker: RiskChecker,
+}
+
+impl RiskLimitedClient {
+    pub fn new(client: DeribitAPIClient, limits: RiskLimits) -> (Self, RiskControl) {
+        let control = RiskControl::new(limits);
+        let client = Self {
+            client,
+            checker: RiskChecker::with_control(control.clone()),
+        };
+        (client, control)
+    }
+
+    pub fn checker(&self) -> &RiskChecker {
+        &self.checker
+    }
+
+    /// To feed the tickers, the user changes and the positions from the subscriptions.
+    pub fn feed(&mut self) -> RiskFeed<'_> {
+        RiskFeed {
+            checker: &mut self.checker,
+        }
+    }
+
+    /// Sends any request but the ones which may trade, see `RiskChecker::check_call`.
+    #[throws(Error)]
+    pub async fn call<'a, R>(&'a mut self, request: R) -> DeribitAPICallResult<R::Response>
+    where
+        R: Request + Serialize + 'a,
+    {
+        self.checker.check_call::<R>()?;
+        self.client.call(request).await?
+    }
+
+    #[throws(Error)]
+    pub async fn buy(&mut self, req: BuyRequest) -> BuyResponse {
+        self.checker.check_buy(&req)?;
+        let resp = self.client.call(req).await?.await?;
+        self.checker.on_trade_response(&resp);
+        resp
+    }
+
+    #[throws(Error)]
+    pub async fn sell(&mut self, req: SellRequest) -> SellResponse {
+        self.checker.check_sell(&req)?;
+        let resp = self.client.call(req).await?.await?;
+        self.checker.on_trade_response(&resp);
+        resp
+    }
+
+    #[throws(Error)]
+    pub async fn edit(&mut self, req: EditRequest) -> EditResponse {
+        self.checker.check_edit(&req)?;
+        let resp = self.client.call(req).await?.await?;
+        self.checker.on_trade_response(&resp);
+        resp
+    }
+
+    /// Loads the positions and the open orders of a currency.
+    #[throws(Error)]
+    pub async fn sync(&mut self, currency: Currency) {
+        let positions = self
+            .client
+            .call(GetPositionsRequest::all(currency.clone()))
+            .await?
+            .await?;
+        for position in &positions {
+            self.checker.on_position(position);
+        }
+        let req = GetOpenOrdersByCurrencyRequest::by_currency(currency);
+        for open in self.client.call(req).await?.await? {
+            self.checker.on_order(&open.order);
+        }
+    }
+
+    /// Engages the kill switch and cancels all the open orders, returning how many were.
+    #[throws(Error)]
+    pub async fn kill(&mut self) -> usize {
+        self.checker.kill();
+        let cancelled = self.client.call(CancelAllRequest).await?.await?;
+        self.checker.open_orders.clear();
+        cancelled.0
+    }
+}
+
+fn signed(direction: Direction, amount: f64) -> f64 {
+    match direction {
+        Direction::Buy => amount,
+        Direction::Sell => -amount,
+        Direction::Zero => 0.,
+    }
+}
+
+// Inverse futures are sized in USD, options in the base currency and the linear
+// instruments in the base currency too
+fn notional(instrument_name: &str, amount: f64, mark: &Mark) -> f64 {
+    match instrument_name.parse::<InstrumentName>() {
+        Ok(InstrumentName::Option { .. }) => amount * mark.index_price,
+        Ok(instrument) if !instrument.underlying().contains('_') => amount,
+        _ => amount * mark.mark_price,
+    }
+}
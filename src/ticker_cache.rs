@@ -0,0 +1,147 @@
+use crate::models::subscription::{
+    IncrementalTickerData, IncrementalTickerType, Stats, TickerData,
+};
+use std::collections::HashMap;
+
+/// The latest ticker of every instrument, rebuilt from the `incremental_ticker.*`
+/// notifications.
+///
+/// A snapshot replaces the ticker of its instrument, and the changes following it are
+/// merged field by field. Changes of instruments without a snapshot yet are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct TickerCache {
+    tickers: HashMap<String, TickerData>,
+}
+
+impl TickerCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a notification, returning the updated ticker of its instrument.
+    pub fn on_update(&mut self, data: &IncrementalTickerData) -> Option<&TickerData> {
+        match data.ty {
+            IncrementalTickerType::Snapshot => {
+                let ticker = snapshot(data)?;
+                self.tickers.insert(data.instrument_name.clone(), ticker);
+            }
+            IncrementalTickerType::Change => {
+                merge(self.tickers.get_mut(&data.instrument_name)?, data);
+            }
+        }
+        self.tickers.get(&data.instrument_name)
+    }
+
+    pub fn get(&self, instrument_name: &str) -> Option<&TickerData> {
+        self.tickers.get(instrument_name)
+    }
+
+    pub fn instruments(&self) -> impl Iterator<Item = &str> {
+        self.tickers.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tickers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tickers.is_empty()
+    }
+
+    /// Forgets an instrument, e.g. after unsubscribing or once it expired.
+    pub fn remove(&mut self, instrument_name: &str) -> Option<TickerData> {
+        self.tickers.remove(instrument_name)
+    }
+
+    /// Forgets every instrument, to be called on reconnection as the snapshots are resent.
+    pub fn clear(&mut self) {
+        self.tickers.clear()
+    }
+}
+
+fn snapshot(data: &IncrementalTickerData) -> Option<TickerData> {
+    Some(TickerData {
+        ask_iv: data.ask_iv,
+        best_ask_amount: data.best_ask_amount?,
+        best_ask_price: data.best_ask_price,
+        best_bid_amount: data.best_bid_amount?,
+        best_bid_price: data.best_bid_price,
+        bid_iv: data.bid_iv,
+        current_funding: data.current_funding,
+        delivery_price: data.delivery_price,
+        funding_8h: data.funding_8h,
+        greeks: data.greeks.and_then(|greeks| greeks.complete()),
+        index_price: data.index_price?,
+        instrument_name: data.instrument_name.clone(),
+        interest_rate: data.interest_rate,
+        last_price: data.last_price,
+        mark_iv: data.mark_iv,
+        mark_price: data.mark_price?,
+        max_price: data.max_price?,
+        min_price: data.min_price?,
+        open_interest: data.open_interest?,
+        settlement_price: data.settlement_price,
+        state: data.state?,
+        stats: data.stats.clone().unwrap_or(Stats {
+            high: None,
+            low: None,
+            volume: None,
+        }),
+        timestamp: data.timestamp,
+        underlying_index: data.underlying_index.clone(),
+        underlying_price: data.underlying_price,
+    })
+}
+
+fn merge(ticker: &mut TickerData, data: &IncrementalTickerData) {
+    fn set<T: Clone>(field: &mut T, value: &Option<T>) {
+        if let Some(value) = value {
+            *field = value.clone();
+        }
+    }
+    fn set_opt<T: Clone>(field: &mut Option<T>, value: &Option<T>) {
+        if value.is_some() {
+            *field = value.clone();
+        }
+    }
+
+    set_opt(&mut ticker.ask_iv, &data.ask_iv);
+    set(&mut ticker.best_ask_amount, &data.best_ask_amount);
+    set_opt(&mut ticker.best_ask_price, &data.best_ask_price);
+    set(&mut ticker.best_bid_amount, &data.best_bid_amount);
+    set_opt(&mut ticker.best_bid_price, &data.best_bid_price);
+    set_opt(&mut ticker.bid_iv, &data.bid_iv);
+    set_opt(&mut ticker.current_funding, &data.current_funding);
+    set_opt(&mut ticker.delivery_price, &data.delivery_price);
+    set_opt(&mut ticker.funding_8h, &data.funding_8h);
+    if let Some(update) = &data.greeks {
+        match &mut ticker.greeks {
+            Some(greeks) => {
+                set(&mut greeks.delta, &update.delta);
+                set(&mut greeks.gamma, &update.gamma);
+                set(&mut greeks.rho, &update.rho);
+                set(&mut greeks.theta, &update.theta);
+                set(&mut greeks.vega, &update.vega);
+            }
+            None => ticker.greeks = update.complete(),
+        }
+    }
+    set(&mut ticker.index_price, &data.index_price);
+    set_opt(&mut ticker.interest_rate, &data.interest_rate);
+    set_opt(&mut ticker.last_price, &data.last_price);
+    set_opt(&mut ticker.mark_iv, &data.mark_iv);
+    set(&mut ticker.mark_price, &data.mark_price);
+    set(&mut ticker.max_price, &data.max_price);
+    set(&mut ticker.min_price, &data.min_price);
+    set(&mut ticker.open_interest, &data.open_interest);
+    set_opt(&mut ticker.settlement_price, &data.settlement_price);
+    set(&mut ticker.state, &data.state);
+    if let Some(stats) = &data.stats {
+        set_opt(&mut ticker.stats.high, &stats.high);
+        set_opt(&mut ticker.stats.low, &stats.low);
+        set_opt(&mut ticker.stats.volume, &stats.volume);
+    }
+    set_opt(&mut ticker.underlying_index, &data.underlying_index);
+    set_opt(&mut ticker.underlying_price, &data.underlying_price);
+    ticker.timestamp = data.timestamp;
+}
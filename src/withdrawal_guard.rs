@@ -0,0 +1,373 @@
+use crate::errors::DeribitError;
+use crate::models::{
+    AddressBookType, Currency, GetAddressBookRequest, SubmitTransferToSubaccountRequest,
+    SubmitTransferToUserRequest, TransferItem, WithdrawRequest, Withdrawal,
+};
+use crate::DeribitAPIClient;
+use chrono::{DateTime, NaiveDate, Utc};
+use failure::Error;
+use fehler::{throw, throws};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Funds leaving the account.
+#[derive(Debug, Clone)]
+pub enum Movement {
+    Withdraw(WithdrawRequest),
+    TransferToUser(SubmitTransferToUserRequest),
+    TransferToSubaccount(SubmitTransferToSubaccountRequest),
+}
+
+impl Movement {
+    pub fn currency(&self) -> &Currency {
+        match self {
+            Movement::Withdraw(req) => &req.currency,
+            Movement::TransferToUser(req) => &req.currency,
+            Movement::TransferToSubaccount(req) => &req.currency,
+        }
+    }
+
+    pub fn amount(&self) -> f64 {
+        match self {
+            Movement::Withdraw(req) => req.amount,
+            Movement::TransferToUser(req) => req.amount,
+            Movement::TransferToSubaccount(req) => req.amount,
+        }
+    }
+
+    /// The address, or the id of the subaccount, receiving the funds.
+    pub fn destination(&self) -> String {
+        match self {
+            Movement::Withdraw(req) => req.address.clone(),
+            Movement::TransferToUser(req) => req.destination.clone(),
+            Movement::TransferToSubaccount(req) => req.destination.to_string(),
+        }
+    }
+}
+
+impl From<WithdrawRequest> for Movement {
+    fn from(req: WithdrawRequest) -> Self {
+        Movement::Withdraw(req)
+    }
+}
+
+impl From<SubmitTransferToUserRequest> for Movement {
+    fn from(req: SubmitTransferToUserRequest) -> Self {
+        Movement::TransferToUser(req)
+    }
+}
+
+impl From<SubmitTransferToSubaccountRequest> for Movement {
+    fn from(req: SubmitTransferToSubaccountRequest) -> Self {
+        Movement::TransferToSubaccount(req)
+    }
+}
+
+/// Why the guard refused a movement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WithdrawalRejection {
+    DestinationNotAllowed {
+        currency: Currency,
+        destination: String,
+    },
+    NoDailyCap(Currency),
+    DailyCapExceeded {
+        currency: Currency,
+        cap: f64,
+        spent: f64,
+        amount: f64,
+    },
+}
+
+impl fmt::Display for WithdrawalRejection {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WithdrawalRejection::DestinationNotAllowed {
+                currency,
+                destination,
+            } => write!(
+                fmt,
+                "{} destination {} is not allowed",
+                currency, destination
+            ),
+            WithdrawalRejection::NoDailyCap(currency) => {
+                write!(fmt, "no daily cap set for {}", currency)
+            }
+            WithdrawalRejection::DailyCapExceeded {
+                currency,
+                cap,
+                spent,
+                amount,
+            } => write!(
+                fmt,
+                "{} {} on top of {} spent today exceeds the daily cap of {}",
+                amount, currency, spent, cap
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WithdrawalRejection {}
+
+#[derive(Debug, Clone)]
+pub enum AuditOutcome {
+    /// The movement passed the checks and awaits execution
+    DryRun,
+    Rejected(WithdrawalRejection),
+    Sent {
+        id: String,
+    },
+    /// Refused by Deribit, the amount no longer counts against the cap
+    Failed(String),
+    /// No answer, e.g. a timeout: the movement may have gone out, so its amount keeps
+    /// counting against the cap
+    Unknown(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub movement: Movement,
+    pub outcome: AuditOutcome,
+}
+
+/// A movement which passed the dry run of `WithdrawalGuard::prepare`, the only way to
+/// get one executed. It is used up by `execute`, so one dry run sends at most once.
+#[derive(Debug)]
+pub struct PendingMovement {
+    movement: Movement,
+    prepared_at: DateTime<Utc>,
+}
+
+impl PendingMovement {
+    pub fn movement(&self) -> &Movement {
+        &self.movement
+    }
+
+    pub fn prepared_at(&self) -> DateTime<Utc> {
+        self.prepared_at
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Receipt {
+    Withdrawal(Withdrawal),
+    Transfer(TransferItem),
+}
+
+/// A policy layer in front of withdrawals and transfers, which cannot be undone.
+///
+/// Funds only go to allowlisted destinations, within a daily cap per currency, and only
+/// after a dry run: `prepare` checks the movement and hands out a `PendingMovement`
+/// which `execute` then sends, checking again. Every attempt and its outcome is recorded
+/// in the audit log.
+///
+/// Subaccounts are allowlisted by their id. Currencies without a daily cap are blocked,
+/// and the caps count the funds sent during the UTC day. The amount is reserved before
+/// sending and only released when Deribit refuses the movement.
+#[derive(Debug, Clone, Default)]
+pub struct WithdrawalGuard {
+    allowlist: HashMap<Currency, HashSet<String>>,
+    daily_caps: HashMap<Currency, f64>,
+    spent: HashMap<(Currency, NaiveDate), f64>,
+    audit_log: Vec<AuditEntry>,
+}
+
+impl WithdrawalGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow(&mut self, currency: Currency, destination: &str) {
+        self.allowlist
+            .entry(currency)
+            .or_default()
+            .insert(destination.to_string());
+    }
+
+    pub fn revoke(&mut self, currency: &Currency, destination: &str) {
+        if let Some(destinations) = self.allowlist.get_mut(currency) {
+            destinations.remove(destination);
+        }
+    }
+
+    pub fn is_allowed(&self, currency: &Currency, destination: &str) -> bool {
+        self.allowlist
+            .get(currency)
+            .map_or(false, |destinations| destinations.contains(destination))
+    }
+
+    /// Allowlists the withdrawal and transfer addresses of the Deribit address book.
+    #[throws(Error)]
+    pub async fn load_address_book(&mut self, client: &mut DeribitAPIClient, currency: Currency) {
+        for r#type in [AddressBookType::Withdrawal, AddressBookType::Transfer].iter() {
+            let req = GetAddressBookRequest::new(currency.clone(), *r#type);
+            for entry in client.call(req).await?.await? {
+                self.allow(entry.currency, &entry.address);
+            }
+        }
+    }
+
+    pub fn set_daily_cap(&mut self, currency: Currency, cap: f64) {
+        self.daily_caps.insert(currency, cap);
+    }
+
+    pub fn spent_on(&self, currency: &Currency, day: NaiveDate) -> f64 {
+        self.spent
+            .get(&(currency.clone(), day))
+            .copied()
+            .unwrap_or(0.)
+    }
+
+    pub fn check(
+        &self,
+        movement: &Movement,
+        now: DateTime<Utc>,
+    ) -> Result<(), WithdrawalRejection> {
+        let currency = movement.currency();
+        let destination = movement.destination();
+        if !self.is_allowed(currency, &destination) {
+            return Err(WithdrawalRejection::DestinationNotAllowed {
+                currency: currency.clone(),
+                destination,
+            });
+        }
+
+        let cap = *self
+            .daily_caps
+            .get(currency)
+            .ok_or_else(|| WithdrawalRejection::NoDailyCap(currency.clone()))?;
+        let spent = self.spent_on(currency, now.date_naive());
+        if spent + movement.amount() > cap {
+            return Err(WithdrawalRejection::DailyCapExceeded {
+                currency: currency.clone(),
+                cap,
+                spent,
+                amount: movement.amount(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Dry runs a movement against the policy.
+    #[throws(WithdrawalRejection)]
+    pub fn prepare<M: Into<Movement>>(
+        &mut self,
+        movement: M,
+        now: DateTime<Utc>,
+    ) -> PendingMovement {
+        let movement = movement.into();
+        if let Err(rejection) = self.check(&movement, now) {
+            self.record(now, movement, AuditOutcome::Rejected(rejection.clone()));
+            throw!(rejection);
+        }
+        self.record(now, movement.clone(), AuditOutcome::DryRun);
+        PendingMovement {
+            movement,
+            prepared_at: now,
+        }
+    }
+
+    /// Checks a prepared movement again and reserves its amount against the daily cap,
+    /// the first step of `execute`. Reserved up front, a movement without an answer
+    /// may have gone out.
+    #[throws(WithdrawalRejection)]
+    pub fn begin(&mut self, pending: PendingMovement, now: DateTime<Utc>) -> Movement {
+        let movement = pending.movement;
+        if let Err(rejection) = self.check(&movement, now) {
+            self.record(now, movement, AuditOutcome::Rejected(rejection.clone()));
+            throw!(rejection);
+        }
+        *self
+            .spent
+            .entry((movement.currency().clone(), now.date_naive()))
+            .or_default() += movement.amount();
+        movement
+    }
+
+    /// Records the result of sending a movement from `begin`, the last step of
+    /// `execute`. Only a refusal by Deribit releases the reserved amount.
+    #[throws(Error)]
+    pub fn settle(
+        &mut self,
+        movement: Movement,
+        now: DateTime<Utc>,
+        result: Result<Receipt, Error>,
+    ) -> Receipt {
+        match result {
+            Ok(receipt) => {
+                let id = match &receipt {
+                    Receipt::Withdrawal(withdrawal) => withdrawal.id.to_string(),
+                    Receipt::Transfer(transfer) => transfer.id.to_string(),
+                };
+                self.record(now, movement, AuditOutcome::Sent { id });
+                receipt
+            }
+            Err(e) => {
+                let refused = matches!(
+                    e.downcast_ref::<DeribitError>(),
+                    Some(DeribitError::RemoteError { .. })
+                );
+                if refused {
+                    self.release(movement.currency(), now.date_naive(), movement.amount());
+                    self.record(now, movement, AuditOutcome::Failed(e.to_string()));
+                } else {
+                    self.record(now, movement, AuditOutcome::Unknown(e.to_string()));
+                }
+                throw!(e)
+            }
+        }
+    }
+
+    /// Sends a prepared movement, unless the policy or the spending changed since.
+    #[throws(Error)]
+    pub async fn execute(
+        &mut self,
+        client: &mut DeribitAPIClient,
+        pending: PendingMovement,
+        now: DateTime<Utc>,
+    ) -> Receipt {
+        let movement = self.begin(pending, now)?;
+        let result = match &movement {
+            Movement::Withdraw(req) => send(client.call(req.clone()))
+                .await
+                .map(Receipt::Withdrawal),
+            Movement::TransferToUser(req) => {
+                send(client.call(req.clone())).await.map(Receipt::Transfer)
+            }
+            Movement::TransferToSubaccount(req) => {
+                send(client.call(req.clone())).await.map(Receipt::Transfer)
+            }
+        };
+        self.settle(movement, now, result)?
+    }
+
+    /// Stops counting an amount against the cap of a day, e.g. once a movement of unknown
+    /// outcome turned out not to have been sent.
+    pub fn release(&mut self, currency: &Currency, day: NaiveDate, amount: f64) {
+        if let Some(spent) = self.spent.get_mut(&(currency.clone(), day)) {
+            *spent = (*spent - amount).max(0.);
+        }
+    }
+
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.audit_log
+    }
+
+    fn record(&mut self, timestamp: DateTime<Utc>, movement: Movement, outcome: AuditOutcome) {
+        self.audit_log.push(AuditEntry {
+            timestamp,
+            movement,
+            outcome,
+        });
+    }
+}
+
+// Awaits both the sending and the response of a call
+async fn send<T, F, R>(call: F) -> Result<T, Error>
+where
+    F: std::future::Future<Output = Result<R, Error>>,
+    R: std::future::Future<Output = Result<T, Error>>,
+{
+    call.await?.await
+}
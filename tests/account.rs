@@ -1,8 +1,14 @@
-use deribit::models::{AuthRequest, Currency, GetAccountSummaryRequest, GetSubaccountsRequest};
+use deribit::models::{
+    ApiKeyScope, AuthRequest, ChangeScopeInApiKeyRequest, Currency, GetAccountSummaryRequest,
+    GetSubaccountsRequest, ListApiKeysRequest, ScopeAccess, SimulatePortfolioRequest,
+    ToggleSubaccountLoginRequest,
+};
+use deribit::risk::MarginUtilization;
 use deribit::{Deribit, DeribitBuilder};
 use dotenv::dotenv;
 use failure::Error;
 use fehler::throws;
+use serde_json::{json, to_value};
 use std::env::var;
 use tokio::runtime::Runtime;
 
@@ -40,7 +46,7 @@ fn get_account_summary() {
         let req = AuthRequest::credential_auth(&key, &secret);
         let _ = client.call(req).await?.await?;
         let req = GetAccountSummaryRequest::extended(Currency::BTC);
-        Ok::<_, Error>(client.call(req).await?.await?)
+        client.call(req).await?.await
     };
     let _ = rt.block_on(fut)?;
 }
@@ -60,7 +66,82 @@ fn get_subaccounts() {
         let _ = client.call(req).await?.await?;
 
         let req = GetSubaccountsRequest::with_portfolio();
-        Ok::<_, Error>(client.call(req).await?.await?)
+        client.call(req).await?.await
     };
     let _ = rt.block_on(fut)?;
 }
+
+#[test]
+#[throws(Error)]
+fn simulate_portfolio() {
+    let AccountTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = AccountTest::default();
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = SimulatePortfolioRequest::new(Currency::BTC).position("BTC-PERPETUAL", 1000.);
+        client.call(req).await?.await
+    };
+    let summary = rt.block_on(fut)?;
+    let _ = MarginUtilization::projected(&summary);
+}
+
+#[test]
+#[throws(Error)]
+fn list_api_keys() {
+    let AccountTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = AccountTest::default();
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        client.call(ListApiKeysRequest).await?.await
+    };
+    let keys = rt.block_on(fut)?;
+    assert!(!keys.is_empty());
+}
+
+#[test]
+#[throws(Error)]
+fn api_key_scope() {
+    let scope: ApiKeyScope = "account:read trade:read_write wallet:none mainaccount".parse()?;
+    assert_eq!(scope.account, Some(ScopeAccess::Read));
+    assert_eq!(scope.trade, Some(ScopeAccess::ReadWrite));
+    assert_eq!(scope.wallet, Some(ScopeAccess::None));
+    assert_eq!(scope.block_trade, None);
+    assert_eq!(scope.other, ["mainaccount"]);
+    assert_eq!(
+        scope.to_string(),
+        "account:read trade:read_write wallet:none mainaccount"
+    );
+
+    assert!("trade:write".parse::<ApiKeyScope>().is_err());
+
+    let req = ChangeScopeInApiKeyRequest::new(
+        3,
+        ApiKeyScope::read_only().block_trade(ScopeAccess::ReadWrite),
+    );
+    assert_eq!(
+        to_value(req)?,
+        json!({
+            "id": 3,
+            "max_scope": "account:read trade:read wallet:none block_trade:read_write"
+        })
+    );
+
+    assert_eq!(
+        to_value(ToggleSubaccountLoginRequest::disable(7))?,
+        json!({"sid": 7, "state": "disable"})
+    );
+}
@@ -0,0 +1,117 @@
+use deribit::announcement_watcher::AnnouncementWatcher;
+use deribit::models::subscription::AnnouncementsData;
+use deribit::models::{Announcement, GetAnnouncementsRequest};
+use deribit::DeribitBuilder;
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_value, json};
+use tokio::runtime::Runtime;
+
+#[test]
+#[throws(Error)]
+fn get_announcements() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new()?;
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetAnnouncementsRequest::new();
+        client.call(req).await?.await
+    };
+    let _ = rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn watcher_dedups_notifications() {
+    let notification = |id: u64, date: u64, action: &str| {
+        from_value::<AnnouncementsData>(json!({
+            "action": action,
+            "title": format!("Announcement {}", id),
+            "body": "Scheduled maintenance",
+            "date": date,
+            "id": id,
+            "important": true,
+            "number": id
+        }))
+    };
+
+    let mut watcher = AnnouncementWatcher::new();
+    assert!(watcher
+        .on_notification(&notification(1, 100, "new")?)
+        .is_some());
+    assert!(watcher
+        .on_notification(&notification(2, 200, "new")?)
+        .is_some());
+    assert!(watcher
+        .on_notification(&notification(1, 100, "new")?)
+        .is_none());
+
+    let unread: Vec<_> = watcher.unread().map(|a| a.id).collect();
+    assert_eq!(unread, [2, 1]);
+
+    assert!(watcher
+        .on_notification(&notification(2, 200, "delete")?)
+        .is_none());
+    assert!(watcher.get(2).is_none());
+    assert_eq!(watcher.announcements().count(), 1);
+    assert!(watcher.is_unread(1));
+}
+
+fn announcement(id: u64, date: u64) -> Announcement {
+    from_value(json!({
+        "id": id,
+        "title": format!("Announcement {}", id),
+        "body": "Scheduled maintenance",
+        "date": date,
+        "important": false,
+        "number": id
+    }))
+    .unwrap()
+}
+
+#[test]
+fn watcher_pages_back_through_history() {
+    let mut watcher = AnnouncementWatcher::new();
+    let req = GetAnnouncementsRequest::before(1000, 2);
+
+    let (added, older) = watcher.on_history(&req, vec![announcement(5, 500), announcement(4, 400)]);
+    assert_eq!(added.len(), 2);
+    let older = older.expect("a full page has older announcements");
+    assert_eq!(older.start_timestamp, Some(400));
+    assert_eq!(older.count, Some(2));
+
+    // Announcement 4 shares the boundary date and is only added once
+    let (added, older) =
+        watcher.on_history(&older, vec![announcement(4, 400), announcement(3, 400)]);
+    assert_eq!(added.iter().map(|a| a.id).collect::<Vec<_>>(), [3]);
+    let older = older.unwrap();
+
+    let (added, older) = watcher.on_history(&older, vec![announcement(1, 100)]);
+    assert_eq!(added.len(), 1);
+    assert!(older.is_none());
+
+    // Reloading stops at the first page bringing nothing new
+    let (added, older) = watcher.on_history(&req, vec![announcement(5, 500), announcement(4, 400)]);
+    assert!(added.is_empty());
+    assert!(older.is_none());
+
+    let ids: Vec<_> = watcher.announcements().map(|a| a.id).collect();
+    assert_eq!(ids, [5, 4, 3, 1]);
+    assert_eq!(watcher.unread().count(), 0);
+}
+
+#[test]
+fn watcher_replaces_unread() {
+    let mut watcher = AnnouncementWatcher::new();
+    let added = watcher.on_unread(vec![announcement(1, 100), announcement(2, 200)]);
+    assert_eq!(added.len(), 2);
+
+    // Read elsewhere, announcement 1 is no longer unread
+    let added = watcher.on_unread(vec![announcement(2, 200), announcement(3, 300)]);
+    assert_eq!(added.iter().map(|a| a.id).collect::<Vec<_>>(), [3]);
+    let unread: Vec<_> = watcher.unread().map(|a| a.id).collect();
+    assert_eq!(unread, [3, 2]);
+    assert!(!watcher.is_unread(1));
+    assert!(watcher.get(1).is_some());
+}
@@ -0,0 +1,87 @@
+use deribit::block_trade_flow::{execute_between, ExecutionFailed};
+use deribit::models::{
+    AuthRequest, BlockTradeLeg, BlockTradeProposal, BlockTradeRole, Direction, SignedBlockTrade,
+};
+use deribit::{DeribitBuilder, DeribitError};
+use dotenv::dotenv;
+use failure::{err_msg, Error};
+use fehler::throws;
+use serde_json::{from_str, to_string, to_value};
+use std::env::var;
+use tokio::runtime::Runtime;
+
+#[test]
+#[throws(Error)]
+fn signature_exchange() {
+    let proposal = BlockTradeProposal::new(vec![
+        BlockTradeLeg::new("BTC-PERPETUAL", 8000., 200000., Direction::Buy),
+        BlockTradeLeg::new("BTC-28JUN19-8000-C", 0.1, 100., Direction::Sell),
+    ]);
+    assert_ne!(proposal.nonce, BlockTradeProposal::new(vec![]).nonce);
+
+    let verify = to_value(proposal.verify(BlockTradeRole::Maker))?;
+    assert_eq!(verify["role"], "maker");
+    assert_eq!(verify["trades"][1]["direction"], "sell");
+
+    // The signed proposal travels to the counterparty, which executes as taker
+    let signed = proposal
+        .clone()
+        .signed(BlockTradeRole::Maker, "signature".into());
+    let received: SignedBlockTrade = from_str(&to_string(&signed)?)?;
+    let execute = to_value(received.execute())?;
+    assert_eq!(execute["role"], "taker");
+    assert_eq!(execute["counterparty_signature"], "signature");
+    assert_eq!(execute["nonce"], verify["nonce"]);
+    assert_eq!(execute["timestamp"], verify["timestamp"]);
+    assert_eq!(execute["trades"], verify["trades"]);
+}
+
+#[test]
+fn execution_failure_keeps_the_execute_error() {
+    let failed = ExecutionFailed {
+        execute: err_msg("execution refused"),
+        invalidate: err_msg("timeout"),
+    };
+    assert_eq!(
+        failed.to_string(),
+        "execution refused, and invalidating the signature failed: timeout"
+    );
+}
+
+#[test]
+#[throws(Error)]
+fn execute_between_with_itself() {
+    let _ = dotenv();
+
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = || DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut signer, _) = drb().connect().await?;
+        let (mut executor, _) = drb().connect().await?;
+        for client in [&mut signer, &mut executor].iter_mut() {
+            let req = AuthRequest::credential_auth(&key, &secret);
+            let _ = client.call(req).await?.await?;
+        }
+
+        // An account cannot trade with itself, the signature gets invalidated
+        let proposal = BlockTradeProposal::new(vec![BlockTradeLeg::new(
+            "BTC-PERPETUAL",
+            8000.,
+            200000.,
+            Direction::Buy,
+        )]);
+        let result =
+            execute_between(&mut signer, &mut executor, proposal, BlockTradeRole::Maker).await;
+        let e = result.err().unwrap();
+        assert!(e.downcast_ref::<ExecutionFailed>().is_none());
+        assert!(matches!(
+            e.downcast_ref::<DeribitError>(),
+            Some(DeribitError::RemoteError { .. })
+        ));
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
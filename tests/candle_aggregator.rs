@@ -0,0 +1,138 @@
+use chrono::Duration;
+use deribit::candle_aggregator::CandleAggregator;
+use deribit::models::subscription::{ChartTradesData, TradesData};
+use deribit::models::{Candle, Resolution};
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_str, json};
+
+const MINUTE: u64 = 60_000;
+const START: u64 = 1_600_000_020 * 1000 - 1_600_000_020 * 1000 % (5 * MINUTE);
+
+#[throws(Error)]
+fn trade(seq: u64, timestamp: u64, price: f64, amount: f64) -> TradesData {
+    from_str(
+        &json!({
+            "amount": amount,
+            "direction": "buy",
+            "index_price": price,
+            "instrument_name": "BTC-PERPETUAL",
+            "price": price,
+            "tick_direction": 0,
+            "timestamp": timestamp,
+            "trade_id": seq.to_string(),
+            "trade_seq": seq
+        })
+        .to_string(),
+    )?
+}
+
+fn candle(timestamp: u64, open: f64, close: f64) -> Candle {
+    Candle {
+        timestamp,
+        open,
+        high: open.max(close),
+        low: open.min(close),
+        close,
+        volume: 1.,
+        cost: 10000.,
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn aggregate_trades() {
+    let mut agg = CandleAggregator::new("BTC-PERPETUAL", Duration::minutes(5));
+
+    // Minute candles are merged into 5 minutes ones
+    agg.seed(
+        &[
+            candle(START + MINUTE, 10010., 10020.),
+            candle(START, 10000., 10010.),
+        ],
+        100,
+    );
+    let seeded = *agg.last().unwrap();
+    assert_eq!(seeded.timestamp, START);
+    assert_eq!(seeded.open, 10000.);
+    assert_eq!(seeded.close, 10020.);
+    assert_eq!(seeded.volume, 2.);
+
+    // Trades included in the seed are skipped
+    assert!(agg
+        .on_trade(&trade(100, START + MINUTE, 9000., 10.)?)
+        .is_none());
+
+    let c = agg
+        .on_trade(&trade(102, START + 3 * MINUTE, 10030., 10030.)?)
+        .unwrap();
+    assert_eq!(c.close, 10030.);
+    assert_eq!(c.high, 10030.);
+    assert_eq!(c.volume, 3.);
+
+    // A late trade does not move the close, a duplicate is skipped
+    let c = agg
+        .on_trade(&trade(101, START + 2 * MINUTE, 9990., 9990.)?)
+        .unwrap();
+    assert_eq!(c.close, 10030.);
+    assert_eq!(c.low, 9990.);
+    assert!(agg
+        .on_trade(&trade(101, START + 2 * MINUTE, 9990., 9990.)?)
+        .is_none());
+
+    // The next period opens with its lowest sequence number, whatever the arrival order
+    agg.on_trade(&trade(104, START + 6 * MINUTE, 10050., 100.)?);
+    let c = agg
+        .on_trade(&trade(103, START + 5 * MINUTE, 10040., 100.)?)
+        .unwrap();
+    assert_eq!(c.timestamp, START + 5 * MINUTE);
+    assert_eq!(c.open, 10040.);
+    assert_eq!(c.close, 10050.);
+
+    assert_eq!(agg.closed(START + 7 * MINUTE).count(), 1);
+    agg.truncate(1);
+    assert_eq!(agg.candles().count(), 1);
+}
+
+#[test]
+#[throws(Error)]
+fn chart_candles_cover_their_period() {
+    let mut agg = CandleAggregator::new("BTC-PERPETUAL", Duration::minutes(5));
+
+    // Seeding the same range twice replaces the candles
+    let seed = [candle(START, 10000., 10010.)];
+    agg.seed(&seed, 100);
+    agg.seed(&seed, 100);
+    assert_eq!(agg.last().unwrap().volume, 1.);
+
+    agg.on_trade(&trade(101, START + MINUTE, 10020., 10020.)?);
+    let chart: ChartTradesData = from_str(
+        &json!({
+            "tick": START + 2 * MINUTE,
+            "open": 10000.0,
+            "high": 10030.0,
+            "low": 10000.0,
+            "close": 10030.0,
+            "volume": 3.0,
+            "cost": 30050.0
+        })
+        .to_string(),
+    )?;
+    assert!(!agg.on_chart(Resolution::OneMinute, &chart));
+    assert!(agg.on_chart(Resolution::FiveMinutes, &chart));
+    let c = *agg.last().unwrap();
+    assert_eq!(c.timestamp, START);
+    assert_eq!(c.volume, 3.);
+
+    // A trade of the period arriving after the chart candle is already counted by it
+    assert!(agg
+        .on_trade(&trade(102, START + 3 * MINUTE, 9000., 9000.)?)
+        .is_none());
+    assert_eq!(agg.last().unwrap().open, 10000.);
+    assert_eq!(agg.last().unwrap().volume, 3.);
+
+    let c = agg
+        .on_trade(&trade(103, START + 5 * MINUTE, 10040., 10040.)?)
+        .unwrap();
+    assert_eq!(c.timestamp, START + 5 * MINUTE);
+}
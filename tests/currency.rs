@@ -0,0 +1,73 @@
+use deribit::currency_registry::CurrencyRegistry;
+use deribit::models::{Currency, GetCurrenciesResponse, GetIndexResponse};
+use deribit::DeribitBuilder;
+use dotenv::dotenv;
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_str, json, to_string};
+use tokio::runtime::Runtime;
+
+#[test]
+#[throws(Error)]
+fn unknown_currencies() {
+    assert_eq!(from_str::<Currency>(r#""btc""#)?, Currency::BTC);
+    assert_eq!(from_str::<Currency>(r#""USDC""#)?, Currency::USDC);
+    let sol: Currency = from_str(r#""SOL""#)?;
+    assert_eq!(sol, Currency::Other("SOL".into()));
+    assert_eq!(to_string(&sol)?, r#""SOL""#);
+    assert_eq!("sol".parse::<Currency>()?, sol);
+    assert!("".parse::<Currency>().is_err());
+
+    let index: GetIndexResponse =
+        from_str(&json!({"BTC": 10000.0, "XRP": 0.3, "edp": 10000.0}).to_string())?;
+    assert_eq!(index.indices[&Currency::Other("XRP".into())], 0.3);
+}
+
+#[test]
+#[throws(Error)]
+fn currency_registry() {
+    let currencies: GetCurrenciesResponse = from_str(
+        &json!([{
+            "coin_type": "BITCOIN",
+            "currency": "BTC",
+            "currency_long": "Bitcoin",
+            "fee_precision": 4,
+            "min_confirmations": 1,
+            "min_withdrawal_fee": 0.0001,
+            "withdrawal_fee": 0.0001,
+            "withdrawal_priorities": [{"name": "high", "value": 1.5}]
+        }, {
+            "coin_type": "SOLANA",
+            "currency": "SOL",
+            "currency_long": "Solana",
+            "fee_precision": 2,
+            "min_confirmations": 30,
+            "min_withdrawal_fee": 0.01,
+            "withdrawal_fee": 0.01
+        }])
+        .to_string(),
+    )?;
+    let registry = CurrencyRegistry::new(currencies);
+    let sol = Currency::Other("SOL".into());
+    assert_eq!(registry.min_confirmations(&sol), Some(30));
+    assert_eq!(registry.withdrawal_fee(&Currency::BTC), Some(0.0001));
+    assert_eq!(registry.round_fee(&sol, 0.01234), 0.01);
+    assert_eq!(registry.fee_precision(&Currency::ETH), None);
+}
+
+#[test]
+#[throws(Error)]
+fn get_currencies() {
+    let _ = dotenv();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let registry = CurrencyRegistry::load(&mut client).await?;
+        assert!(registry.get(&Currency::BTC).is_some());
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
@@ -1,12 +1,21 @@
 use chrono::{Duration, Utc};
 use deribit::models::{
-    Currency, GetBookSummaryByCurrencyRequest, GetFundingRateValueRequest, GetIndexRequest,
-    GetInstrumentsRequest,
+    AssetKind, ChunkedRequest, Currency, FundingChartLength, GetBookSummaryByCurrencyRequest,
+    GetBookSummaryByInstrumentRequest, GetDeliveryPricesRequest, GetFundingChartDataRequest,
+    GetFundingRateHistoryRequest, GetFundingRateValueRequest, GetHistoricalVolatilityRequest,
+    GetIndexRequest, GetInstrumentsRequest, GetLastSettlementsByInstrumentRequest,
+    GetLastTradesByCurrencyAndTimeRequest, GetLastTradesByCurrencyRequest,
+    GetLastTradesByInstrumentAndTimeRequest, GetLastTradesByInstrumentRequest, GetOrderBookRequest,
+    GetTradeVolumesRequest, GetTradingviewChartDataRequest, GetTradingviewChartDataResponse,
+    Resolution, TickerRequest,
 };
+use deribit::order_book::OrderBook;
 use deribit::DeribitBuilder;
 use dotenv::dotenv;
 use failure::Error;
 use fehler::throws;
+use futures::{StreamExt, TryStreamExt};
+use serde_json::{from_str, json, to_value};
 use tokio::runtime::Runtime;
 
 #[test]
@@ -27,7 +36,7 @@ fn get_index() {
 
         Ok::<_, Error>(())
     };
-    let _ = rt.block_on(fut)?;
+    rt.block_on(fut)?;
 }
 
 #[test]
@@ -48,7 +57,7 @@ fn get_instruments() {
 
         Ok::<_, Error>(())
     };
-    let _ = rt.block_on(fut)?;
+    rt.block_on(fut)?;
 }
 
 #[test]
@@ -70,7 +79,7 @@ fn get_book_summary_by_currency() {
 
         Ok::<_, Error>(())
     };
-    let _ = rt.block_on(fut)?;
+    rt.block_on(fut)?;
 }
 
 #[test]
@@ -89,9 +98,159 @@ fn get_funding_rate_value() {
             Utc::now() - Duration::seconds(60),
             Utc::now(),
         );
-        let resp = client.call(req).await?.await?;
+        let _resp = client.call(req).await?.await?;
 
         Ok::<_, Error>(())
     };
-    let _ = rt.block_on(fut)?;
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn order_book_and_ticker() {
+    let _ = dotenv();
+    let _ = env_logger::try_init();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetOrderBookRequest::with_depth("BTC-PERPETUAL", 5);
+        let snapshot = client.call(req).await?.await?;
+        let ob = OrderBook::from_snapshot(&snapshot);
+        assert!(ob.bids().count() <= 5);
+        let req = TickerRequest::new("BTC-PERPETUAL");
+        let _ = client.call(req).await?.await?;
+        let req = GetBookSummaryByInstrumentRequest::new("BTC-PERPETUAL");
+        let _ = client.call(req).await?.await?;
+
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn get_last_trades() {
+    let _ = dotenv();
+    let _ = env_logger::try_init();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let now = Utc::now();
+        let req = GetLastTradesByInstrumentRequest::new("BTC-PERPETUAL");
+        let _ = client.call(req).await?.await?;
+        let req = GetLastTradesByInstrumentAndTimeRequest::new(
+            "BTC-PERPETUAL",
+            now - Duration::hours(1),
+            now,
+        );
+        let _ = client.call(req).await?.await?;
+        let req = GetLastTradesByCurrencyRequest::with_kind(Currency::BTC, AssetKind::Future);
+        let _ = client.call(req).await?.await?;
+        let req = GetLastTradesByCurrencyAndTimeRequest::new(
+            Currency::ETH,
+            now - Duration::hours(1),
+            now,
+        );
+        let _ = client.call(req).await?.await?;
+
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn historical_data() {
+    let _ = dotenv();
+    let _ = env_logger::try_init();
+
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let now = Utc::now();
+        let req = GetFundingRateHistoryRequest::new("BTC-PERPETUAL", now - Duration::days(1), now);
+        let _ = client.call(req).await?.await?;
+        let req = GetFundingChartDataRequest::new("BTC-PERPETUAL", FundingChartLength::OneDay);
+        let _ = client.call(req).await?.await?;
+        let req = GetHistoricalVolatilityRequest::new(Currency::BTC);
+        let _ = client.call(req).await?.await?;
+        let req = GetLastSettlementsByInstrumentRequest::new("BTC-PERPETUAL");
+        let _ = client.call(req).await?.await?;
+        let req = GetTradeVolumesRequest::extended();
+        let _ = client.call(req).await?.await?;
+
+        let req = GetDeliveryPricesRequest {
+            count: Some(10),
+            ..GetDeliveryPricesRequest::new("btc_usd")
+        };
+        let prices: Vec<_> = client.call_paginated(req).take(25).try_collect().await?;
+        assert_eq!(prices.len(), 25);
+
+        // Two days of minute candles span several windows
+        let start = now - Duration::days(2);
+        let req =
+            GetTradingviewChartDataRequest::new("BTC-PERPETUAL", Resolution::OneMinute, start, now);
+        let candles: Vec<_> = client.call_chunked(req).try_collect().await?;
+        assert!(candles.len() > 1000);
+        assert!(candles.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn tradingview_candles() {
+    let resp: GetTradingviewChartDataResponse = from_str(
+        &json!({
+            "status": "ok",
+            "ticks": [1_600_000_000_000u64, 1_600_000_060_000u64],
+            "open": [10000.0, 10010.0],
+            "high": [10020.0, 10015.0],
+            "low": [9990.0, 10000.0],
+            "close": [10010.0, 10005.0],
+            "volume": [1.5, 2.0],
+            "cost": [15000.0, 20000.0]
+        })
+        .to_string(),
+    )?;
+    let candles = resp.candles()?;
+    assert_eq!(candles.len(), 2);
+    assert_eq!(candles[1].timestamp, 1_600_000_060_000);
+    assert_eq!(candles[1].close, 10005.);
+
+    // The candle where two windows meet is yielded once
+    let (first, last) = GetTradingviewChartDataRequest::dedup(candles.clone(), None);
+    assert_eq!((first.len(), last), (2, Some(1_600_000_060_000)));
+    let (second, last) = GetTradingviewChartDataRequest::dedup(candles[1..].to_vec(), last);
+    assert!(second.is_empty());
+    assert_eq!(last, Some(1_600_000_060_000));
+
+    let ragged: GetTradingviewChartDataResponse = from_str(
+        &json!({
+            "status": "ok",
+            "ticks": [1_600_000_000_000u64, 1_600_000_060_000u64],
+            "open": [10000.0, 10010.0],
+            "high": [10020.0],
+            "low": [9990.0, 10000.0],
+            "close": [10010.0, 10005.0],
+            "volume": [1.5, 2.0],
+            "cost": [15000.0, 20000.0]
+        })
+        .to_string(),
+    )?;
+    assert!(ragged.candles().is_err());
+
+    assert_eq!(to_value(Resolution::OneDay)?, json!("1D"));
+    assert_eq!(to_value(Resolution::FifteenMinutes)?, json!("15"));
+    assert_eq!(Resolution::OneHour.duration(), Duration::hours(1));
 }
@@ -0,0 +1,64 @@
+use chrono::{Duration, TimeZone, Utc};
+use deribit::mmp_guard::MmpGuard;
+use deribit::models::{
+    MmpConfig, SetMmpConfigRequest, SubscriptionData, SubscriptionMessage, SubscriptionParams,
+};
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_str, json, to_value};
+
+#[test]
+#[throws(Error)]
+fn mmp_trigger_pauses_quoting() {
+    let msg: SubscriptionMessage = from_str(
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "subscription",
+            "params": {
+                "channel": "user.mmp_trigger.btc_usd",
+                "data": {"index_name": "btc_usd", "frozen_until": 1_600_000_060_000u64}
+            }
+        })
+        .to_string(),
+    )?;
+    let data = match msg.params {
+        SubscriptionParams::Subscription(SubscriptionData::UserMmpTrigger(data)) => data.data,
+        params => panic!("unexpected subscription {:?}", params),
+    };
+
+    let now = Utc.timestamp_millis_opt(1_600_000_000_000).unwrap();
+    let mut guard = MmpGuard::new("btc_usd", Duration::seconds(30));
+    assert!(!guard.is_paused());
+
+    let mut other = data.clone();
+    other.index_name = Some("eth_usd".into());
+    guard.on_trigger(&other, now);
+    assert!(!guard.is_paused());
+
+    guard.on_trigger(&data, now);
+    assert!(guard.is_paused());
+    // The freeze outlasts the cooldown
+    assert_eq!(guard.resume_at(), Some(now + Duration::seconds(60)));
+
+    let mut guard = MmpGuard::new("btc_usd", Duration::seconds(120));
+    guard.on_trigger(&data, now);
+    assert_eq!(guard.resume_at(), Some(now + Duration::seconds(120)));
+}
+
+#[test]
+#[throws(Error)]
+fn mmp_config_serialization() {
+    let mut config = MmpConfig::new("btc_usd", 10, 30, 100.);
+    config.delta_limit = Some(5.);
+    let value = to_value(SetMmpConfigRequest::new(config))?;
+    assert_eq!(
+        value,
+        json!({
+            "index_name": "btc_usd",
+            "interval": 10,
+            "frozen_time": 30,
+            "quantity_limit": 100.,
+            "delta_limit": 5.
+        })
+    );
+}
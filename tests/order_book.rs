@@ -0,0 +1,70 @@
+use deribit::models::subscription::BookData;
+use deribit::models::GetOrderBookResponse;
+use deribit::order_book::OrderBook;
+use deribit::DeribitError;
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_str, json};
+
+#[throws(Error)]
+fn book(prev_change_id: i64, change_id: i64, bids: serde_json::Value) -> BookData {
+    from_str(
+        &json!({
+            "asks": [],
+            "bids": bids,
+            "change_id": change_id,
+            "instrument_name": "BTC-PERPETUAL",
+            "prev_change_id": prev_change_id,
+            "timestamp": 1_600_000_000_000u64 + change_id as u64,
+        })
+        .to_string(),
+    )?
+}
+
+#[test]
+#[throws(Error)]
+fn seed_and_apply_deltas() {
+    let snapshot: GetOrderBookResponse = from_str(
+        &json!({
+            "asks": [[10001.0, 5.0], [10002.0, 7.0]],
+            "bids": [[10000.0, 3.0], [9999.5, 4.0]],
+            "change_id": 10,
+            "best_ask_amount": 5.0,
+            "best_ask_price": 10001.0,
+            "best_bid_amount": 3.0,
+            "best_bid_price": 10000.0,
+            "index_price": 10000.2,
+            "instrument_name": "BTC-PERPETUAL",
+            "mark_price": 10000.4,
+            "max_price": 10150.0,
+            "min_price": 9850.0,
+            "open_interest": 1000.0,
+            "state": "open",
+            "stats": {"high": 10100.0, "low": 9900.0, "volume": 100.0},
+            "timestamp": 1_600_000_000_000u64
+        })
+        .to_string(),
+    )?;
+    let mut ob = OrderBook::from_snapshot(&snapshot);
+    assert_eq!(ob.best_bid(), Some((10000., 3.)));
+    assert_eq!(ob.best_ask(), Some((10001., 5.)));
+
+    // Deltas older than the snapshot are skipped
+    ob.apply(&book(8, 9, json!([["delete", 10000.0, 0.0]]))?)?;
+    assert_eq!(ob.best_bid(), Some((10000., 3.)));
+
+    ob.apply(&book(
+        10,
+        11,
+        json!([["delete", 10000.0, 0.0], ["new", 10000.5, 2.0]]),
+    )?)?;
+    assert_eq!(ob.best_bid(), Some((10000.5, 2.)));
+    assert_eq!(ob.bids().count(), 2);
+    assert_eq!(ob.mid_price(), Some(10000.75));
+    assert_eq!(ob.change_id(), 11);
+
+    match ob.apply(&book(12, 13, json!([]))?) {
+        Err(DeribitError::OrderBookOutOfSync { expected: 11, .. }) => {}
+        r => panic!("unexpected result {:?}", r),
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::{TimeZone, Utc};
+use deribit::models::{InstrumentName, OptionType};
+use deribit::pricing::{implied_vol_coin, implied_vol_usd, time_to_expiry, Black76};
+
+fn assert_close(a: f64, b: f64, tolerance: f64) {
+    assert!((a - b).abs() < tolerance, "{} != {}", a, b);
+}
+
+#[test]
+fn parse_instrument_name() {
+    let name: InstrumentName = "BTC-28JUN19-3000-P".parse().unwrap();
+    assert_eq!(name.strike(), Some(3000.));
+    assert_eq!(name.option_type(), Some(OptionType::Put));
+    assert_eq!(
+        name.expiry(),
+        Some(Utc.with_ymd_and_hms(2019, 6, 28, 8, 0, 0).unwrap())
+    );
+    assert_eq!(name.to_string(), "BTC-28JUN19-3000-P");
+
+    let name: InstrumentName = "ETH-5JUL19".parse().unwrap();
+    assert_eq!(name.underlying(), "ETH");
+    assert_eq!(name.to_string(), "ETH-5JUL19");
+
+    let name: InstrumentName = "XRP_USDC-30AUG24-0d625-C".parse().unwrap();
+    assert_eq!(name.strike(), Some(0.625));
+    assert_eq!(name.to_string(), "XRP_USDC-30AUG24-0d625-C");
+
+    assert_eq!(
+        "BTC-PERPETUAL".parse::<InstrumentName>().unwrap().expiry(),
+        None
+    );
+    assert!("BTC-28XXX19-3000-P".parse::<InstrumentName>().is_err());
+}
+
+#[test]
+fn put_call_parity() {
+    let call = Black76::new(OptionType::Call, 10000., 11000., 0.25, 0.8);
+    let put = Black76 {
+        option_type: OptionType::Put,
+        ..call
+    };
+    assert_close(call.price_usd() - put.price_usd(), 10000. - 11000., 1e-6);
+    assert_close(call.greeks_usd().delta - put.greeks_usd().delta, 1., 1e-9);
+}
+
+#[test]
+fn implied_vol_round_trip() {
+    let now = Utc.with_ymd_and_hms(2019, 5, 1, 8, 0, 0).unwrap();
+    let name: InstrumentName = "BTC-28JUN19-6000-C".parse().unwrap();
+    let model = Black76::from_instrument(&name, 5500., 0.75, now).unwrap();
+    assert_close(model.time, 58. / 365., 1e-12);
+
+    let vol = implied_vol_usd(
+        OptionType::Call,
+        model.forward,
+        model.strike,
+        model.time,
+        model.price_usd(),
+    )
+    .unwrap();
+    assert_close(vol, 0.75, 1e-6);
+
+    let vol = implied_vol_coin(
+        OptionType::Call,
+        model.forward,
+        model.strike,
+        model.time,
+        model.price_coin(),
+    )
+    .unwrap();
+    assert_close(vol, 0.75, 1e-6);
+
+    assert!(implied_vol_usd(OptionType::Call, 5500., 6000., model.time, 6000.).is_none());
+}
+
+#[test]
+fn coin_greeks() {
+    let model = Black76::new(OptionType::Call, 10000., 10000., 0.1, 0.6);
+    let bump = 1e-2;
+    let up = Black76 {
+        forward: model.forward + bump,
+        ..model
+    };
+    let down = Black76 {
+        forward: model.forward - bump,
+        ..model
+    };
+    // Coin delta is the coin value sensitivity scaled back to a coin notional
+    let coin_delta = (up.price_coin() - down.price_coin()) / (2. * bump) * model.forward;
+    assert_close(model.greeks_coin().delta, coin_delta, 1e-6);
+    assert_close(
+        model.greeks_coin().vega,
+        model.greeks_usd().vega / model.forward,
+        1e-12,
+    );
+
+    let expired = Black76 { time: 0., ..model };
+    assert_eq!(expired.price_usd(), 0.);
+    assert_eq!(
+        time_to_expiry(
+            Utc.with_ymd_and_hms(2019, 1, 1, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2019, 1, 2, 8, 0, 0).unwrap()
+        ),
+        0.
+    );
+}
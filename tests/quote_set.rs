@@ -0,0 +1,113 @@
+use deribit::models::{MassQuoteLevel, MassQuoteResponse};
+use deribit::quote_set::{Quote, QuoteSet};
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_str, json};
+use std::collections::BTreeMap;
+
+const CALL: &str = "BTC-27DEC24-60000-C";
+const PUT: &str = "BTC-27DEC24-60000-P";
+
+#[test]
+#[throws(Error)]
+fn quote_set_diff() {
+    let mut set = QuoteSet::new("atm", "options");
+
+    let mut desired = BTreeMap::new();
+    desired.insert(CALL.to_string(), Quote::new((0.05, 10.), (0.055, 10.)));
+    desired.insert(PUT.to_string(), Quote::new((0.04, 10.), (0.045, 10.)));
+    let req = set.update(desired.clone()).unwrap();
+    assert_eq!(req.quotes.len(), 2);
+    assert_eq!(req.mmp_group, "options");
+    let quote_id = req.quote_id;
+    set.on_response(&quote_id, &MassQuoteResponse::default());
+
+    // Nothing changed
+    assert!(set.update(desired.clone()).is_none());
+
+    // Only the changed side of the call is requoted and the put is pulled
+    desired.insert(CALL.to_string(), Quote::new((0.05, 10.), (0.056, 10.)));
+    desired.remove(PUT);
+    let req = set.update(desired.clone()).unwrap();
+    assert_eq!(req.quotes.len(), 2);
+    assert_ne!(req.quote_id, quote_id);
+    let quote_id = req.quote_id.clone();
+    let call = req
+        .quotes
+        .iter()
+        .find(|q| q.instrument_name == CALL)
+        .unwrap();
+    assert_eq!(call.bid, None);
+    assert_eq!(call.ask, Some(MassQuoteLevel::new(0.056, 10.)));
+    let put = req
+        .quotes
+        .iter()
+        .find(|q| q.instrument_name == PUT)
+        .unwrap();
+    assert_eq!(put.bid, Some(MassQuoteLevel::new(0.04, 0.)));
+    assert_eq!(put.ask, Some(MassQuoteLevel::new(0.045, 0.)));
+
+    // A rejected side is resent on the next update
+    let resp: MassQuoteResponse = from_str(
+        &json!({
+            "orders": [],
+            "errors": [{"instrument_name": CALL, "side": "ask", "code": 10041, "message": "error"}]
+        })
+        .to_string(),
+    )?;
+    set.on_response(&quote_id, &resp);
+    let req = set.update(desired.clone()).unwrap();
+    assert_eq!(req.quotes.len(), 1);
+    assert_eq!(req.quotes[0].ask, Some(MassQuoteLevel::new(0.056, 10.)));
+
+    // A failed request leaves the live quotes as they were, so the quotes are resent
+    set.on_failure(&req.quote_id);
+    assert!(set.pending(&req.quote_id).is_none());
+    let req = set.update(desired).unwrap();
+    assert_eq!(req.quotes.len(), 1);
+    set.on_response(&req.quote_id, &MassQuoteResponse::default());
+    assert_eq!(set.live()[CALL].ask, Some((0.056, 10.)));
+
+    set.cancel();
+    assert!(set.live().is_empty());
+}
+
+#[test]
+fn quote_set_requests_in_flight() {
+    let mut set = QuoteSet::new("atm", "options");
+    let call = |ask| {
+        let mut quotes = BTreeMap::new();
+        quotes.insert(CALL.to_string(), Quote::new((0.05, 10.), (ask, 10.)));
+        quotes
+    };
+    let (first, second) = (call(0.055), call(0.057));
+
+    let req1 = set.update(first.clone()).unwrap();
+    // Back to nothing while the first request is in flight, its quotes have to be pulled
+    let pull = set.update(BTreeMap::new()).unwrap();
+    assert_eq!(pull.quotes[0].ask, Some(MassQuoteLevel::new(0.055, 0.)));
+    let req2 = set.update(second.clone()).unwrap();
+    assert_eq!(req2.quotes[0].ask, Some(MassQuoteLevel::new(0.057, 10.)));
+
+    // Each response applies the quotes of its own request
+    set.on_response(&req1.quote_id, &MassQuoteResponse::default());
+    assert_eq!(set.live(), &first);
+    assert!(set.pending(&pull.quote_id).is_some());
+    set.on_response(&req2.quote_id, &MassQuoteResponse::default());
+    assert_eq!(set.live(), &second);
+
+    // A late response of a superseded request is ignored
+    set.on_response(&pull.quote_id, &MassQuoteResponse::default());
+    assert_eq!(set.live(), &second);
+    set.on_response("other-1", &MassQuoteResponse::default());
+    assert_eq!(set.live(), &second);
+
+    // Nothing to send leaves the request in flight pending
+    let req3 = set.update(first.clone()).unwrap();
+    let req4 = set.update(second.clone()).unwrap();
+    set.on_failure(&req3.quote_id);
+    assert!(set.update(second.clone()).is_none());
+    assert_eq!(set.pending(&req4.quote_id), Some(&second));
+    set.on_response(&req4.quote_id, &MassQuoteResponse::default());
+    assert_eq!(set.live(), &second);
+}
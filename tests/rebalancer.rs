@@ -0,0 +1,147 @@
+use chrono::{TimeZone, Utc};
+use deribit::models::{Currency, GetSubaccountsResponse, TransferItem};
+use deribit::rebalancer::{MarginBand, PlannedTransfer, SubaccountRebalancer};
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_value, json};
+
+#[throws(Error)]
+fn account(id: u64, equity: f64, initial_margin: f64) -> GetSubaccountsResponse {
+    from_value(json!({
+        "email": format!("user{}@example.com", id),
+        "id": id,
+        "is_password": true,
+        "login_enabled": true,
+        "not_confirmed_email": null,
+        "portfolio": {
+            "btc": {
+                "available_funds": equity - initial_margin,
+                "available_withdrawal_funds": equity - initial_margin,
+                "balance": equity,
+                "currency": "BTC",
+                "equity": equity,
+                "initial_margin": initial_margin,
+                "maintenance_margin": initial_margin / 2.,
+                "margin_balance": equity
+            }
+        },
+        "receive_notifications": false,
+        "system_name": format!("user{}", id),
+        "tfa_enabled": false,
+        "type": "subaccount",
+        "username": format!("user{}", id)
+    }))?
+}
+
+#[throws(Error)]
+fn main_account(id: u64, equity: f64, initial_margin: f64) -> GetSubaccountsResponse {
+    let mut account = account(id, equity, initial_margin)?;
+    account.r#type = "main".into();
+    account
+}
+
+#[test]
+#[throws(Error)]
+fn plan_within_bands() {
+    let mut rebalancer = SubaccountRebalancer::new();
+    rebalancer.set_band(
+        Currency::BTC,
+        MarginBand::new(0.2, 0.4, 0.6).with_min_transfer(0.01),
+    );
+
+    let accounts = [
+        // Ratio 0.8, needs 1.5 more equity to reach 0.4
+        account(1, 1.5, 1.2)?,
+        // Ratio 0.5, within the band
+        account(2, 2., 1.)?,
+        // Ratio 0.1, 4 spare above the target
+        main_account(3, 5., 0.4)?,
+        // Ratio 0.1 as well, but its transfers cannot be looked up when retrying
+        account(4, 10., 1.)?,
+    ];
+
+    let plan = rebalancer.plan("round-1", &accounts);
+    assert_eq!(plan.len(), 1);
+    let transfer = &plan[0];
+    assert_eq!((transfer.source, transfer.destination), (3, 1));
+    assert!((transfer.amount - 1.5).abs() < 1e-9);
+    assert_eq!(transfer.id, "round-1:BTC:3:1");
+
+    // Planning the same round again gives the same ids
+    assert_eq!(rebalancer.plan("round-1", &accounts), plan);
+    assert!(!rebalancer.is_done(transfer));
+
+    // Nothing to do once balanced
+    let balanced = [account(1, 3., 1.2)?, main_account(3, 3.5, 0.4)?];
+    let plan = rebalancer.plan("round-2", &balanced);
+    assert!(plan.is_empty(), "{:?}", plan);
+}
+
+#[throws(Error)]
+fn listed(id: i64, amount: f64, other_side: &str, created_timestamp: u64) -> TransferItem {
+    from_value(json!({
+        "amount": amount,
+        "created_timestamp": created_timestamp,
+        "currency": "BTC",
+        "direction": "payment",
+        "id": id,
+        "other_side": other_side,
+        "state": "confirmed",
+        "type": "subaccount",
+        "updated_timestamp": created_timestamp
+    }))?
+}
+
+#[test]
+#[throws(Error)]
+fn retry_recovers_transfers_in_flight() {
+    let mut rebalancer = SubaccountRebalancer::new();
+    rebalancer.set_band(Currency::BTC, MarginBand::new(0.2, 0.4, 0.6));
+    let accounts = [account(1, 1.5, 1.2)?, main_account(3, 5., 0.4)?];
+    let transfer = rebalancer.plan("round-1", &accounts).remove(0);
+
+    let attempted_at = Utc.timestamp_millis_opt(1_600_000_000_000).unwrap();
+    rebalancer.begin(&transfer, attempted_at);
+    let in_flight = rebalancer.in_flight(&transfer).unwrap().clone();
+    assert_eq!((in_flight.source, in_flight.destination), (3, 1));
+    assert!(!rebalancer.is_done(&transfer));
+
+    let since = attempted_at.timestamp_millis() as u64;
+    let unrelated = [
+        // Another amount, another destination, or long before the attempt
+        listed(10, 1., "user1", since)?,
+        listed(11, transfer.amount, "user2", since)?,
+        listed(12, transfer.amount, "user1", since - 3_600_000)?,
+    ];
+    assert!(!unrelated
+        .iter()
+        .any(|item| in_flight.matches(item, 3, "user1")));
+    assert!(!rebalancer.recover(&transfer, 3, "user1", &unrelated));
+    assert!(!rebalancer.is_done(&transfer));
+
+    // The clock of Deribit being a little behind ours
+    let sent = [listed(13, transfer.amount, "user1", since - 1000)?];
+    assert!(!rebalancer.recover(&transfer, 4, "user1", &sent));
+    assert!(rebalancer.recover(&transfer, 3, "user1", &sent));
+    assert!(rebalancer.is_done(&transfer));
+    assert!(rebalancer.in_flight(&transfer).is_none());
+
+    // A transfer already claimed is not taken for another one
+    let mut again = transfer.clone();
+    again.id = "round-2:BTC:3:1".into();
+    rebalancer.begin(&again, attempted_at);
+    assert!(!rebalancer.recover(&again, 3, "user1", &sent));
+
+    // Nor is a payment of the main account taken for a transfer from another subaccount
+    let from_subaccount = PlannedTransfer {
+        id: "round-2:BTC:4:1".into(),
+        currency: Currency::BTC,
+        source: 4,
+        destination: 1,
+        amount: 2.,
+    };
+    rebalancer.begin(&from_subaccount, attempted_at);
+    let listed_for_main = [listed(14, 2., "user1", since)?];
+    assert!(!rebalancer.recover(&from_subaccount, 3, "user1", &listed_for_main));
+    assert!(!rebalancer.is_done(&from_subaccount));
+}
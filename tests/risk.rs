@@ -0,0 +1,145 @@
+use deribit::models::{GetAccountSummaryResponse, GetPositionsResponse};
+use deribit::risk::{
+    closest_to_liquidation, greeks_by_underlying, liquidation_distance, MarginUtilization,
+};
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_value, json, Value};
+
+#[throws(Error)]
+fn position(instrument_name: &str, direction: &str, extra: Value) -> GetPositionsResponse {
+    let mut position = json!({
+        "average_price": 10000.0,
+        "delta": 1.0,
+        "direction": direction,
+        "estimated_liquidation_price": null,
+        "floating_profit_loss": 0.0,
+        "index_price": 10000.0,
+        "initial_margin": 0.1,
+        "instrument_name": instrument_name,
+        "kind": if instrument_name.ends_with("-C") || instrument_name.ends_with("-P") { "option" } else { "future" },
+        "maintenance_margin": 0.05,
+        "mark_price": 10000.0,
+        "open_orders_margin": 0.0,
+        "realized_profit_loss": 0.0,
+        "settlement_price": 10000.0,
+        "size": 10000.0,
+        "total_profit_loss": 0.0
+    });
+    for (key, value) in extra.as_object().unwrap() {
+        position[key] = value.clone();
+    }
+    from_value(position)?
+}
+
+#[test]
+#[throws(Error)]
+fn liquidation_distances() {
+    let long = position(
+        "BTC-PERPETUAL",
+        "buy",
+        json!({"estimated_liquidation_price": 8000.0}),
+    )?;
+    assert!((liquidation_distance(&long).unwrap() - 0.2).abs() < 1e-12);
+
+    let short = position(
+        "ETH-PERPETUAL",
+        "sell",
+        json!({"estimated_liquidation_price": 10500.0}),
+    )?;
+    assert!((liquidation_distance(&short).unwrap() - 0.05).abs() < 1e-12);
+
+    let option = position("BTC-27MAR20-10000-C", "buy", json!({}))?;
+    assert_eq!(liquidation_distance(&option), None);
+
+    let positions = [long, short, option];
+    let closest: Vec<_> = closest_to_liquidation(&positions)
+        .into_iter()
+        .map(|(instrument, _)| instrument)
+        .collect();
+    assert_eq!(closest, ["ETH-PERPETUAL", "BTC-PERPETUAL"]);
+}
+
+#[test]
+#[throws(Error)]
+fn aggregated_greeks() {
+    let positions = [
+        position("BTC-PERPETUAL", "buy", json!({"delta": 0.5}))?,
+        position(
+            "BTC-27MAR20-10000-C",
+            "buy",
+            json!({"delta": 0.3, "gamma": 0.001, "vega": 2.0, "theta": -1.0}),
+        )?,
+        position(
+            "BTC-27MAR20-9000-P",
+            "sell",
+            json!({"delta": 0.2, "gamma": -0.0005, "vega": -1.5, "theta": 0.5}),
+        )?,
+        position("ETH-PERPETUAL", "sell", json!({"delta": -3.0}))?,
+    ];
+
+    let greeks = greeks_by_underlying(&positions);
+    assert_eq!(greeks.len(), 2);
+    let btc = greeks["BTC"];
+    assert!((btc.delta - 1.0).abs() < 1e-12);
+    assert!((btc.gamma - 0.0005).abs() < 1e-12);
+    assert!((btc.vega - 0.5).abs() < 1e-12);
+    assert!((btc.theta + 0.5).abs() < 1e-12);
+    assert_eq!(greeks["ETH"].delta, -3.0);
+}
+
+#[throws(Error)]
+fn summary(margin_balance: f64, extra: Value) -> GetAccountSummaryResponse {
+    let mut summary = json!({
+        "available_funds": margin_balance,
+        "available_withdrawal_funds": margin_balance,
+        "balance": margin_balance,
+        "currency": "BTC",
+        "delta_total": 0.0,
+        "equity": margin_balance,
+        "futures_pl": 0.0,
+        "futures_session_rpl": 0.0,
+        "futures_session_upl": 0.0,
+        "initial_margin": 0.5,
+        "maintenance_margin": 0.25,
+        "margin_balance": margin_balance,
+        "options_delta": 0.0,
+        "options_gamma": 0.0,
+        "options_pl": 0.0,
+        "options_session_rpl": 0.0,
+        "options_session_upl": 0.0,
+        "options_theta": 0.0,
+        "options_vega": 0.0,
+        "session_rpl": 0.0,
+        "session_upl": 0.0,
+        "total_pl": 0.0
+    });
+    for (key, value) in extra.as_object().unwrap() {
+        summary[key] = value.clone();
+    }
+    from_value(summary)?
+}
+
+#[test]
+#[throws(Error)]
+fn margin_utilization() {
+    assert_eq!(
+        MarginUtilization::new(&summary(2., json!({}))?),
+        Some(MarginUtilization {
+            initial: 0.25,
+            maintenance: 0.125
+        })
+    );
+    let projected = summary(2., json!({ "projected_initial_margin": 1.0 }))?;
+    assert_eq!(
+        MarginUtilization::projected(&projected),
+        Some(MarginUtilization {
+            initial: 0.5,
+            maintenance: 0.125
+        })
+    );
+
+    // An empty account has no utilization rather than an infinite one
+    assert_eq!(MarginUtilization::new(&summary(0., json!({}))?), None);
+    assert_eq!(MarginUtilization::projected(&summary(0., json!({}))?), None);
+}
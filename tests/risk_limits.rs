@@ -0,0 +1,265 @@
+use deribit::models::subscription::{IncrementalTickerData, UserChangesData};
+use deribit::models::{
+    BuyRequest, CancelAllRequest, ClosePositionRequest, EditRequest, ExecuteBlockTradeRequest,
+    GetPositionsRequest, GetPositionsResponse, MassQuoteRequest, SellRequest, TickerRequest,
+};
+use deribit::risk_limits::{InstrumentLimits, RiskChecker, RiskControl, RiskLimits, RiskViolation};
+use deribit::ticker_cache::TickerCache;
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_value, json, Value};
+
+#[throws(Error)]
+fn with_ticker(checker: &mut RiskChecker, instrument_name: &str, mark_price: f64) {
+    let snapshot: IncrementalTickerData = from_value(json!({
+        "type": "snapshot",
+        "instrument_name": instrument_name,
+        "timestamp": 1,
+        "best_ask_amount": 10.0,
+        "best_bid_amount": 10.0,
+        "index_price": 10000.0,
+        "mark_price": mark_price,
+        "max_price": mark_price * 1.1,
+        "min_price": mark_price * 0.9,
+        "open_interest": 1000.0,
+        "state": "open",
+        "stats": {}
+    }))?;
+    let mut cache = TickerCache::new();
+    checker.on_ticker(cache.on_update(&snapshot).unwrap());
+}
+
+fn user_order(order_id: &str, direction: &str, amount: f64, filled: f64, state: &str) -> Value {
+    json!({
+        "amount": amount,
+        "api": true,
+        "average_price": 0.0,
+        "commission": 0.0,
+        "creation_timestamp": 1,
+        "direction": direction,
+        "filled_amount": filled,
+        "instrument_name": "BTC-PERPETUAL",
+        "is_liquidation": false,
+        "label": "",
+        "last_update_timestamp": 1,
+        "max_show": amount,
+        "order_id": order_id,
+        "order_state": state,
+        "order_type": "limit",
+        "post_only": false,
+        "price": 10000.0,
+        "profit_loss": 0.0,
+        "reduce_only": false,
+        "time_in_force": "good_til_cancelled",
+        "replaced": false,
+        "web": false
+    })
+}
+
+#[throws(Error)]
+fn user_changes(orders: Vec<Value>, positions: Vec<Value>) -> UserChangesData {
+    from_value(json!({
+        "instrument_name": "BTC-PERPETUAL",
+        "orders": orders,
+        "trades": [],
+        "positions": positions
+    }))?
+}
+
+fn position(size: f64) -> Value {
+    json!({
+        "average_price": 10000.0,
+        "delta": 0.06,
+        "direction": if size < 0. { "sell" } else { "buy" },
+        "floating_profit_loss": 0.0,
+        "index_price": 10000.0,
+        "initial_margin": 0.001,
+        "instrument_name": "BTC-PERPETUAL",
+        "kind": "future",
+        "maintenance_margin": 0.0005,
+        "mark_price": 10000.0,
+        "open_orders_margin": 0.0,
+        "realized_profit_loss": 0.0,
+        "settlement_price": 10000.0,
+        "size": size,
+        "total_profit_loss": 0.0
+    })
+}
+
+#[test]
+#[throws(Error)]
+fn order_checks() {
+    let limits = RiskLimits::new()
+        .with_default(InstrumentLimits {
+            max_order_size: Some(1000.),
+            max_position: None,
+        })
+        .with_instrument(
+            "BTC-PERPETUAL",
+            InstrumentLimits {
+                max_order_size: Some(50000.),
+                max_position: None,
+            },
+        )
+        .with_max_notional(20000.)
+        .with_price_band(0.05);
+    let mut checker = RiskChecker::new(limits);
+
+    let order = BuyRequest::limit("BTC-PERPETUAL", 10000., 10000.);
+    assert_eq!(
+        checker.check_buy(&order),
+        Err(RiskViolation::NoMarkPrice("BTC-PERPETUAL".into()))
+    );
+    with_ticker(&mut checker, "BTC-PERPETUAL", 10000.)?;
+    assert_eq!(checker.check_buy(&order), Ok(()));
+
+    assert!(matches!(
+        checker.check_buy(&BuyRequest::limit("BTC-PERPETUAL", 10000., 60000.)),
+        Err(RiskViolation::OrderSizeExceeded { limit, .. }) if limit == 50000.
+    ));
+    assert!(matches!(
+        checker.check_buy(&BuyRequest::limit("BTC-PERPETUAL", 10000., 30000.)),
+        Err(RiskViolation::NotionalExceeded { .. })
+    ));
+    assert!(matches!(
+        checker.check_sell(&SellRequest::limit("BTC-PERPETUAL", 10600., 100.)),
+        Err(RiskViolation::PriceOutOfBand { .. })
+    ));
+    assert_eq!(
+        checker.check_sell(&SellRequest::market("BTC-PERPETUAL", 100.)),
+        Ok(())
+    );
+
+    // Linear instruments are sized in the base currency
+    with_ticker(&mut checker, "BTC_USDC-PERPETUAL", 10000.)?;
+    assert!(matches!(
+        checker.check_buy(&BuyRequest::limit("BTC_USDC-PERPETUAL", 10000., 3.)),
+        Err(RiskViolation::NotionalExceeded { notional, .. }) if notional == 30000.
+    ));
+}
+
+#[test]
+#[throws(Error)]
+fn positions_open_orders_and_kill_switch() {
+    let limits = RiskLimits::new()
+        .with_default(InstrumentLimits {
+            max_order_size: None,
+            max_position: Some(1000.),
+        })
+        .with_max_open_orders(2);
+    let mut checker = RiskChecker::new(limits);
+
+    let snapshot: GetPositionsResponse = from_value(position(600.))?;
+    checker.on_position(&snapshot);
+    checker.on_user_changes(&user_changes(
+        vec![user_order("1", "buy", 300., 0., "open")],
+        vec![],
+    )?);
+    assert_eq!(checker.position("BTC-PERPETUAL"), 600.);
+    assert_eq!(checker.open_orders(), 1);
+
+    assert!(matches!(
+        checker.check_buy(&BuyRequest::market("BTC-PERPETUAL", 200.)),
+        Err(RiskViolation::PositionExceeded { position, .. }) if position == 1100.
+    ));
+    assert_eq!(
+        checker.check_buy(&BuyRequest::market("BTC-PERPETUAL", 100.)),
+        Ok(())
+    );
+    assert_eq!(
+        checker.check_sell(&SellRequest::market("BTC-PERPETUAL", 1500.)),
+        Ok(())
+    );
+
+    // The edited order replaces the open one
+    assert_eq!(
+        checker.check_edit(&EditRequest::new("1", 10000., 400.)),
+        Ok(())
+    );
+    assert!(matches!(
+        checker.check_edit(&EditRequest::new("1", 10000., 500.)),
+        Err(RiskViolation::PositionExceeded { .. })
+    ));
+    assert_eq!(
+        checker.check_edit(&EditRequest::new("2", 10000., 100.)),
+        Err(RiskViolation::UnknownOrder("2".into()))
+    );
+
+    checker.on_user_changes(&user_changes(
+        vec![user_order("2", "sell", 100., 0., "open")],
+        vec![],
+    )?);
+    assert_eq!(
+        checker.check_sell(&SellRequest::market("BTC-PERPETUAL", 100.)),
+        Err(RiskViolation::TooManyOpenOrders { limit: 2 })
+    );
+    checker.on_user_changes(&user_changes(
+        vec![user_order("2", "sell", 100., 0., "cancelled")],
+        vec![],
+    )?);
+    assert_eq!(checker.open_orders(), 1);
+
+    // A resting order leaving the book filled moves the position along
+    checker.on_user_changes(&user_changes(
+        vec![user_order("1", "buy", 300., 300., "filled")],
+        vec![position(900.)],
+    )?);
+    assert_eq!(checker.open_orders(), 0);
+    assert_eq!(checker.position("BTC-PERPETUAL"), 900.);
+    assert!(matches!(
+        checker.check_buy(&BuyRequest::market("BTC-PERPETUAL", 200.)),
+        Err(RiskViolation::PositionExceeded { position, .. }) if position == 1100.
+    ));
+
+    checker.kill();
+    assert_eq!(
+        checker.check_sell(&SellRequest::market("BTC-PERPETUAL", 100.)),
+        Err(RiskViolation::KillSwitchEngaged)
+    );
+    checker.reset_kill_switch();
+    assert!(!checker.is_killed());
+}
+
+#[test]
+fn call_refuses_trading_methods() {
+    let checker = RiskChecker::new(RiskLimits::new());
+    assert_eq!(
+        checker.check_call::<ExecuteBlockTradeRequest>(),
+        Err(RiskViolation::UncheckedOrder(
+            "private/execute_block_trade".into()
+        ))
+    );
+    assert!(checker.check_call::<ClosePositionRequest>().is_err());
+    assert!(checker.check_call::<MassQuoteRequest>().is_err());
+    assert!(checker.check_call::<BuyRequest>().is_err());
+
+    assert_eq!(checker.check_call::<CancelAllRequest>(), Ok(()));
+    assert_eq!(checker.check_call::<GetPositionsRequest>(), Ok(()));
+    assert_eq!(checker.check_call::<TickerRequest>(), Ok(()));
+}
+
+#[test]
+fn limits_set_through_the_control() {
+    let control = RiskControl::new(RiskLimits::new());
+    let checker = RiskChecker::with_control(control.clone());
+    let order = SellRequest::market("BTC-PERPETUAL", 100.);
+    assert_eq!(checker.check_sell(&order), Ok(()));
+
+    control.set_limits(RiskLimits::new().with_default(InstrumentLimits {
+        max_order_size: Some(10.),
+        max_position: None,
+    }));
+    assert!(matches!(
+        checker.check_sell(&order),
+        Err(RiskViolation::OrderSizeExceeded { .. })
+    ));
+
+    control.kill();
+    assert!(checker.is_killed());
+    assert_eq!(
+        checker.check_sell(&order),
+        Err(RiskViolation::KillSwitchEngaged)
+    );
+    control.reset_kill_switch();
+    assert!(!checker.is_killed());
+}
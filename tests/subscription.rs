@@ -1,3 +1,5 @@
+#![allow(clippy::let_underscore_future)]
+
 use deribit::models::subscription::{PrivateSubscribeRequest, PublicSubscribeRequest};
 use deribit::models::{
     AuthRequest, BuyRequest, CancelRequest, SellRequest, SubscriptionData, SubscriptionMessage,
@@ -8,6 +10,7 @@ use dotenv::dotenv;
 use failure::Error;
 use fehler::throws;
 use futures::StreamExt;
+use serde_json::{from_str, json, to_value};
 use std::env::var;
 use tokio::runtime::Runtime;
 use tokio::time::{sleep, Duration};
@@ -240,6 +243,71 @@ fn markprice_options() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn chart_trades() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await.unwrap();
+
+        let req = PublicSubscribeRequest::new(&["chart.trades.BTC-PERPETUAL.1".into()]);
+        client.call(req).await.unwrap().await.unwrap();
+
+        let v = subscription.take(1).collect::<Vec<_>>().await;
+        Ok::<_, Error>(v)
+    };
+
+    let v = rt.block_on(fut)?;
+    assert_eq!(v.len(), 1);
+
+    for v in v {
+        match v {
+            Ok(SubscriptionMessage {
+                params: SubscriptionParams::Subscription(SubscriptionData::ChartTrades(..)),
+                ..
+            }) => {}
+            _ => panic!(),
+        }
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn incremental_ticker() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await.unwrap();
+
+        let req = PublicSubscribeRequest::new(&[
+            "incremental_ticker.BTC-PERPETUAL".into(),
+            "price_index.btc_usd".into(),
+            "deribit_volatility_index.btc_usd".into(),
+        ]);
+        client.call(req).await.unwrap().await.unwrap();
+
+        let v = subscription.take(6).collect::<Vec<_>>().await;
+        Ok::<_, Error>(v)
+    };
+
+    let v = rt.block_on(fut)?;
+    assert_eq!(v.len(), 6);
+
+    for v in v {
+        match v {
+            Ok(SubscriptionMessage {
+                params:
+                    SubscriptionParams::Subscription(
+                        SubscriptionData::IncrementalTicker(..)
+                        | SubscriptionData::PriceIndex(..)
+                        | SubscriptionData::DeribitVolatilityIndex(..),
+                    ),
+                ..
+            }) => {}
+            _ => panic!(),
+        }
+    }
+}
+
 #[test]
 #[throws(Error)]
 fn perpetual() {
@@ -443,6 +511,133 @@ fn user_orders() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn user_changes() {
+    let SubscriptionTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = SubscriptionTest::default();
+
+    let fut = async move {
+        let (mut client, subscription) = drb.connect().await?;
+
+        let _ = client
+            .call(AuthRequest::credential_auth(&key, &secret))
+            .await?
+            .await?;
+
+        let req = PrivateSubscribeRequest::new(&["user.changes.BTC-PERPETUAL.raw".into()]);
+        let _ = client.call(req).await?.await?;
+
+        sleep(Duration::from_secs(1)).await;
+
+        let req = BuyRequest::limit("BTC-PERPETUAL", 100f64, 10f64);
+
+        let resp = client.call(req).await?.await?;
+        let id = resp.0.order.order_id;
+        sleep(Duration::from_secs(1)).await;
+
+        let v = subscription.take(1).collect::<Vec<_>>().await;
+        let req = CancelRequest::new(&id);
+        client.call(req).await?.await?;
+        Ok::<_, Error>((id, v))
+    };
+    let (id, v) = rt.block_on(fut)?;
+
+    for v in v {
+        match v {
+            Ok(SubscriptionMessage {
+                params: SubscriptionParams::Subscription(SubscriptionData::UserChanges(data)),
+                ..
+            }) => assert!(data.data.orders.iter().any(|o| o.order_id == id)),
+            _ => panic!(),
+        }
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn account_notifications() {
+    let notification = |channel: &str, data| {
+        from_str::<SubscriptionMessage>(
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "subscription",
+                "params": {"channel": channel, "data": data}
+            })
+            .to_string(),
+        )
+    };
+
+    let msg = notification("user.lock", json!({"currency": "ALL", "locked": true}))?;
+    match msg.params {
+        SubscriptionParams::Subscription(SubscriptionData::UserLock(lock)) => {
+            assert_eq!(lock.data.currency, "ALL");
+            assert!(lock.data.locked);
+        }
+        _ => panic!(),
+    }
+
+    let msg = notification(
+        "user.access_log",
+        json!({
+            "timestamp": 1575876682576u64,
+            "log": "success",
+            "ip": "127.0.0.1",
+            "id": 243,
+            "country": "Netherlands",
+            "city": "Amsterdam"
+        }),
+    )?;
+    match msg.params {
+        SubscriptionParams::Subscription(SubscriptionData::UserAccessLog(log)) => {
+            assert_eq!(log.data.log, "success");
+        }
+        _ => panic!(),
+    }
+
+    let msg = notification(
+        "instrument.state.any.any",
+        json!({
+            "timestamp": 1553080940000u64,
+            "state": "deactivated",
+            "instrument_name": "BTC-22MAR19"
+        }),
+    )?;
+    match msg.params {
+        SubscriptionParams::Subscription(SubscriptionData::InstrumentState(state)) => {
+            assert_eq!(to_value(&state.channel)?, json!("instrument.state.any.any"));
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn platform_state() {
+    let msg: SubscriptionMessage = from_str(
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "subscription",
+            "params": {
+                "channel": "platform_state",
+                "data": {"price_index": "btc_usd", "locked": true}
+            }
+        })
+        .to_string(),
+    )?;
+
+    match msg.params {
+        SubscriptionParams::Subscription(SubscriptionData::PlatformState(state)) => {
+            assert_eq!(state.data.index_lock(), Some(("btc_usd", true)));
+        }
+        _ => panic!(),
+    }
+}
+
 #[test]
 #[throws(Error)]
 fn user_portfolio() {
@@ -0,0 +1,95 @@
+use deribit::models::subscription::IncrementalTickerData;
+use deribit::ticker_cache::TickerCache;
+use failure::Error;
+use fehler::throws;
+use serde_json::{from_value, json, Value};
+
+#[throws(Error)]
+fn update(data: Value) -> IncrementalTickerData {
+    from_value(data)?
+}
+
+#[test]
+#[throws(Error)]
+fn snapshot_then_changes() {
+    let mut cache = TickerCache::new();
+
+    let change = update(json!({
+        "type": "change",
+        "instrument_name": "BTC-PERPETUAL",
+        "timestamp": 1,
+        "best_bid_price": 9000.0
+    }))?;
+    assert!(cache.on_update(&change).is_none());
+    assert!(cache.is_empty());
+
+    let snapshot = update(json!({
+        "type": "snapshot",
+        "instrument_name": "BTC-PERPETUAL",
+        "timestamp": 2,
+        "best_ask_amount": 10.0,
+        "best_ask_price": 9001.0,
+        "best_bid_amount": 20.0,
+        "best_bid_price": 9000.5,
+        "index_price": 9000.7,
+        "mark_price": 9000.8,
+        "max_price": 9100.0,
+        "min_price": 8900.0,
+        "open_interest": 1000.0,
+        "state": "open",
+        "stats": {"high": 9200.0, "low": 8800.0, "volume": 5.0}
+    }))?;
+    let ticker = cache.on_update(&snapshot).unwrap();
+    assert_eq!(ticker.best_bid_price, Some(9000.5));
+
+    let change = update(json!({
+        "type": "change",
+        "instrument_name": "BTC-PERPETUAL",
+        "timestamp": 3,
+        "best_bid_amount": 5.0,
+        "stats": {"volume": 6.0}
+    }))?;
+    cache.on_update(&change);
+
+    let ticker = cache.get("BTC-PERPETUAL").unwrap();
+    assert_eq!(ticker.timestamp, 3);
+    assert_eq!(ticker.best_bid_amount, 5.);
+    assert_eq!(ticker.best_bid_price, Some(9000.5));
+    assert_eq!(ticker.best_ask_price, Some(9001.));
+    assert_eq!(ticker.stats.volume, Some(6.));
+    assert_eq!(ticker.stats.high, Some(9200.));
+    assert_eq!(cache.instruments().collect::<Vec<_>>(), ["BTC-PERPETUAL"]);
+}
+
+#[test]
+#[throws(Error)]
+fn partial_greeks() {
+    let mut cache = TickerCache::new();
+    let snapshot = update(json!({
+        "type": "snapshot",
+        "instrument_name": "BTC-27DEC24-60000-C",
+        "timestamp": 1,
+        "best_ask_amount": 10.0,
+        "best_bid_amount": 20.0,
+        "greeks": {"delta": 0.5, "gamma": 0.0001, "rho": 10.0, "theta": -50.0, "vega": 80.0},
+        "index_price": 60000.0,
+        "mark_price": 0.05,
+        "max_price": 0.1,
+        "min_price": 0.0001,
+        "open_interest": 100.0,
+        "state": "open",
+        "stats": {}
+    }))?;
+    cache.on_update(&snapshot);
+
+    let change = update(json!({
+        "type": "change",
+        "instrument_name": "BTC-27DEC24-60000-C",
+        "timestamp": 2,
+        "greeks": {"delta": 0.55}
+    }))?;
+    let greeks = cache.on_update(&change).unwrap().greeks.clone().unwrap();
+    assert_eq!(greeks.delta, 0.55);
+    assert_eq!(greeks.vega, 80.);
+    assert_eq!(greeks.theta, -50.);
+}
@@ -1,13 +1,24 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use deribit::legged_order::{Leg, LeggedOrder};
 use deribit::models::{
-    AuthRequest, BuyRequest, CancelByLabelRequest, CancelRequest, Currency, EditRequest,
-    GetOpenOrdersByCurrencyRequest, GetOpenOrdersByInstrumentRequest, GetOrderStateRequest,
-    SellRequest,
+    AuthRequest, BuyRequest, CancelByLabelRequest, CancelRequest, ClosePositionRequest, Currency,
+    Direction, EditRequest, GetComboDetailsRequest, GetComboIdsRequest, GetCombosRequest,
+    GetMarginsRequest, GetOpenOrdersByCurrencyRequest, GetOpenOrdersByInstrumentRequest,
+    GetOrderHistoryByCurrencyRequest, GetOrderMarginByIdsRequest, GetOrderStateRequest,
+    GetSettlementHistoryByCurrencyRequest, GetUserTradesByInstrumentAndTimeRequest,
+    GetUserTradesResponse, Order, OtocoOrder, PaginatedRequest, SellRequest, TradeRequest, Trigger,
+    TriggerFillCondition,
 };
+use deribit::order_group::OrderGroup;
 use deribit::DeribitBuilder;
 use dotenv::dotenv;
 use failure::Error;
 use fehler::throws;
+use futures::TryStreamExt;
+use serde_json::{from_str, json, to_value};
+use std::convert::TryFrom;
 use std::env::var;
+use std::ops::DerefMut;
 use std::time::Duration;
 use tokio::runtime::Runtime;
 use tokio::time::sleep;
@@ -28,7 +39,7 @@ fn get_order_state() {
         let _ = client.call(req).await?.await?;
 
         let req = GetOrderStateRequest::new("2320198993");
-        Ok::<_, Error>(client.call(req).await?.await?)
+        client.call(req).await?.await
     };
     let _ = rt.block_on(fut)?;
 }
@@ -60,7 +71,7 @@ fn buy_and_sell() {
             .await?;
         Ok::<_, Error>(())
     };
-    let _ = rt.block_on(fut)?;
+    rt.block_on(fut)?;
 }
 
 #[test]
@@ -103,7 +114,7 @@ fn buy_and_edit_and_inspect_and_cancel() {
         client.call(CancelRequest::new(&id)).await?.await?;
         Ok::<_, Error>(())
     };
-    let _ = rt.block_on(fut)?;
+    rt.block_on(fut)?;
 }
 
 #[test]
@@ -121,16 +132,392 @@ fn buy_and_cancel_by_label() {
         let req = AuthRequest::credential_auth(&key, &secret);
         let _ = client.call(req).await?.await?;
 
+        let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.).with_label("happy");
+
+        client.call(req).await?.await?;
+
+        let cancelled = client
+            .call(CancelByLabelRequest::new("happy"))
+            .await?
+            .await?;
+        assert_eq!(*cancelled, 1);
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn order_group() {
+    let _ = dotenv();
+
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let group = OrderGroup::new("group");
         let mut req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
-        req.label = Some("happy".to_string());
+        group.tag(req.deref_mut());
+        client.call(req).await?.await?;
 
-        client.call(req).await?.await?.0.order.order_id;
+        let orders = group
+            .open_orders(&mut client, &[Currency::BTC, Currency::ETH])
+            .await?;
+        assert_eq!(orders.len(), 1);
+        group.edit(&mut client, "BTC-PERPETUAL", 1100., 20.).await?;
+        assert_eq!(group.cancel(&mut client, Some(Currency::BTC)).await?, 1);
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
 
+#[test]
+fn order_group_tags_requests() {
+    let group = OrderGroup::new("group");
+    let mut buy = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
+    group.tag(buy.deref_mut());
+    assert_eq!(to_value(&buy).unwrap()["label"], "group");
+
+    let mut sell = SellRequest::limit("BTC-PERPETUAL", 1100., 10.).with_label("other");
+    group.tag(&mut sell);
+    assert_eq!(sell.label.as_deref(), Some("group"));
+}
+
+#[test]
+#[throws(Error)]
+fn margins_and_close_position() {
+    let _ = dotenv();
+
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
         client
-            .call(CancelByLabelRequest::new("happy"))
+            .call(GetMarginsRequest::try_from(&*req)?)
+            .await?
+            .await?;
+
+        let order = client.call(req).await?.await?.0.order;
+        client
+            .call(GetOrderMarginByIdsRequest::new(vec![order
+                .order_id
+                .clone()]))
+            .await?
+            .await?;
+        client
+            .call(CancelRequest::new(&order.order_id))
+            .await?
+            .await?;
+
+        client
+            .call(BuyRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+        client
+            .call(ClosePositionRequest::market("BTC-PERPETUAL"))
             .await?
             .await?;
         Ok::<_, Error>(())
     };
-    let _ = rt.block_on(fut)?;
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn margins_need_a_price() {
+    let req = GetMarginsRequest::try_from(&*BuyRequest::limit("BTC-PERPETUAL", 9000., 10.))?;
+    assert_eq!(req.price, 9000.);
+    assert!(GetMarginsRequest::try_from(&*BuyRequest::market("BTC-PERPETUAL", 10.)).is_err());
+}
+
+#[test]
+#[throws(Error)]
+fn get_user_trades_and_settlements_paginated() {
+    let _ = dotenv();
+
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let mut req = GetUserTradesByInstrumentAndTimeRequest::new(
+            "BTC-PERPETUAL",
+            Utc::now() - ChronoDuration::days(1),
+            Utc::now(),
+        );
+        req.count = Some(10);
+        let _ = client.call_paginated(req).try_collect::<Vec<_>>().await?;
+
+        let mut req = GetSettlementHistoryByCurrencyRequest::new(Currency::BTC);
+        req.count = Some(5);
+        let _ = client.call_paginated(req).try_collect::<Vec<_>>().await?;
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+fn trade(trade_id: &str, timestamp: u64) -> serde_json::Value {
+    json!({
+        "amount": 10.0,
+        "direction": "buy",
+        "fee": 0.0,
+        "fee_currency": "BTC",
+        "index_price": 8000.0,
+        "instrument_name": "BTC-PERPETUAL",
+        "iv": null,
+        "label": null,
+        "liquidity": "T",
+        "matching_id": null,
+        "order_id": "1",
+        "order_type": "market",
+        "price": 8000.0,
+        "self_trade": false,
+        "state": "filled",
+        "tick_direction": 0,
+        "timestamp": timestamp,
+        "trade_id": trade_id,
+        "trade_seq": 1
+    })
+}
+
+#[test]
+#[throws(Error)]
+fn paginate_user_trades_by_time() {
+    let req = GetUserTradesByInstrumentAndTimeRequest {
+        count: Some(3),
+        ..Default::default()
+    };
+
+    let page: GetUserTradesResponse = from_str(
+        &json!({
+            "trades": [trade("1", 100), trade("2", 200), trade("3", 200)],
+            "has_more": true
+        })
+        .to_string(),
+    )?;
+    let (trades, next) = req.paginate(page)?;
+    assert_eq!(trades.len(), 3);
+    let next = next.unwrap();
+    assert_eq!(next.start_timestamp, 200);
+
+    // Trades sharing the boundary timestamp are not yielded twice
+    let page: GetUserTradesResponse = from_str(
+        &json!({
+            "trades": [trade("2", 200), trade("3", 200), trade("4", 300)],
+            "has_more": false
+        })
+        .to_string(),
+    )?;
+    let (trades, next) = next.paginate(page)?;
+    assert_eq!(
+        trades
+            .iter()
+            .map(|t| t.trade_id.as_str())
+            .collect::<Vec<_>>(),
+        ["4"]
+    );
+    assert!(next.is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn paginate_user_trades_sharing_a_timestamp() {
+    let req = GetUserTradesByInstrumentAndTimeRequest {
+        count: Some(500),
+        start_timestamp: 200,
+        skip_trade_ids: vec!["1".into(), "2".into()],
+        ..Default::default()
+    };
+
+    // A page of already yielded trades asks for the same timestamp with a larger page
+    let page: GetUserTradesResponse = from_str(
+        &json!({
+            "trades": [trade("1", 200), trade("2", 200)],
+            "has_more": true
+        })
+        .to_string(),
+    )?;
+    let (trades, next) = req.paginate(page.clone())?;
+    assert!(trades.is_empty());
+    let next = next.unwrap();
+    assert_eq!(next.start_timestamp, 200);
+    assert_eq!(next.count, Some(1000));
+
+    // Past the largest page the trades cannot be reached
+    assert!(next.paginate(page).is_err());
+}
+
+fn order(order_id: usize) -> serde_json::Value {
+    json!({
+        "amount": 10.0,
+        "api": true,
+        "creation_timestamp": 1,
+        "direction": "buy",
+        "instrument_name": "BTC-PERPETUAL",
+        "is_liquidation": false,
+        "last_update_timestamp": 1,
+        "max_show": 10.0,
+        "order_id": order_id.to_string(),
+        "order_state": "filled",
+        "order_type": "limit",
+        "post_only": false,
+        "price": 8000.0,
+        "profit_loss": 0.0,
+        "reduce_only": false,
+        "time_in_force": "good_til_cancelled"
+    })
+}
+
+#[test]
+#[throws(Error)]
+fn paginate_order_history_without_count() {
+    let req = GetOrderHistoryByCurrencyRequest::new(Currency::BTC);
+
+    // A full page of the default size might not be the last one
+    let page: Vec<Order> = from_str(&json!((0..20).map(order).collect::<Vec<_>>()).to_string())?;
+    let (orders, next) = req.paginate(page)?;
+    assert_eq!(orders.len(), 20);
+    let next = next.unwrap();
+    assert_eq!(next.offset, Some(20));
+
+    let page: Vec<Order> = from_str(&json!((20..25).map(order).collect::<Vec<_>>()).to_string())?;
+    let (orders, next) = next.paginate(page)?;
+    assert_eq!(orders.len(), 5);
+    assert!(next.is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn combos_and_legged_order() {
+    let _ = dotenv();
+
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let ids = client
+            .call(GetComboIdsRequest::active(Currency::BTC))
+            .await?
+            .await?;
+        if let Some(id) = ids.first() {
+            let combo = client.call(GetComboDetailsRequest::new(id)).await?.await?;
+            assert_eq!(&combo.id, id);
+        }
+        client
+            .call(GetCombosRequest::new(Currency::BTC))
+            .await?
+            .await?;
+
+        let mut order = LeggedOrder::new(
+            "calendar",
+            10.,
+            vec![
+                Leg::buy("BTC-PERPETUAL", 1., 1000.),
+                Leg::sell("BTC-PERPETUAL", 1., 1_000_000.),
+            ],
+        )?;
+        order.place(&mut client).await?;
+        order.refresh(&mut client).await?;
+        assert_eq!(order.filled_amount(), 0.);
+        assert!(order.imbalance().is_empty());
+        assert!(!order.is_done());
+        assert_eq!(order.cancel(&mut client, Currency::BTC).await?, 2);
+        assert!(order.is_done());
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+fn legged_order_validates_legs() {
+    let zero = Leg {
+        direction: Direction::Zero,
+        ..Leg::buy("BTC-PERPETUAL", 1., 1000.)
+    };
+    assert!(LeggedOrder::new("zero", 1., vec![zero]).is_err());
+    assert!(LeggedOrder::new("ratio", 1., vec![Leg::buy("BTC-PERPETUAL", 0., 1000.)]).is_err());
+    assert!(LeggedOrder::new("ok", 1., vec![Leg::sell("BTC-PERPETUAL", 2., 1000.)]).is_ok());
+}
+
+#[test]
+#[throws(Error)]
+fn otoco_request_serialization() {
+    let req = BuyRequest(
+        TradeRequest::limit("BTC-PERPETUAL", 50000., 100.).one_triggers_one_cancels_other(
+            TriggerFillCondition::FirstHit,
+            vec![
+                OtocoOrder::take_limit(Direction::Sell, 55000., 55000.).reduce_only(),
+                OtocoOrder::trailing_stop(Direction::Sell, 500.).reduce_only(),
+            ],
+        ),
+    );
+    let value = to_value(&req)?;
+    assert_eq!(value["linked_order_type"], "one_triggers_one_cancels_other");
+    assert_eq!(value["trigger_fill_condition"], "first_hit");
+    assert_eq!(value["otoco_config"][0]["type"], "take_limit");
+    assert_eq!(value["otoco_config"][0]["trigger_price"], 55000.);
+    assert_eq!(value["otoco_config"][1]["type"], "trailing_stop");
+    assert_eq!(value["otoco_config"][1]["trigger_offset"], 500.);
+    assert!(value.get("mmp").is_none());
+
+    let req = TradeRequest::trailing_stop("BTC-PERPETUAL", 100., 10.)
+        .with_mmp()
+        .with_display_amount(10.);
+    let value = to_value(&req)?;
+    assert_eq!(value["type"], "trailing_stop");
+    assert_eq!(value["mmp"], true);
+    assert_eq!(value["display_amount"], 10.);
+}
+
+#[test]
+#[throws(Error)]
+fn order_builder() {
+    let req = Order::buy("BTC-PERPETUAL")
+        .limit(10000.)
+        .amount(10.)
+        .post_only()
+        .label("x")
+        .build();
+    let value = to_value(&req)?;
+    assert_eq!(value["type"], "limit");
+    assert_eq!(value["price"], 10000.);
+    assert_eq!(value["amount"], 10.);
+    assert_eq!(value["post_only"], true);
+    assert_eq!(value["label"], "x");
+
+    let req: SellRequest = Order::sell("BTC-PERPETUAL")
+        .amount(10.)
+        .stop_limit(9000., 8900.)
+        .trigger(Trigger::MarkPrice)
+        .reduce_only()
+        .build();
+    let value = to_value(&req)?;
+    assert_eq!(value["type"], "stop_limit");
+    assert_eq!(value["stop_price"], 9000.);
+    assert_eq!(value["trigger"], "mark_price");
+    assert_eq!(value["reduce_only"], true);
 }
@@ -0,0 +1,96 @@
+use chrono::{TimeZone, Utc};
+use deribit::models::subscription::MarkPriceOptionData;
+use deribit::models::InstrumentName;
+use deribit::pricing::VolSurface;
+use serde_json::from_str;
+
+fn assert_close(a: f64, b: f64, tolerance: f64) {
+    assert!((a - b).abs() < tolerance, "{} != {}", a, b);
+}
+
+fn surface() -> VolSurface {
+    let data: Vec<MarkPriceOptionData> = from_str(
+        r#"[
+            {"instrument_name":"BTC-28JUN19-8000-C","iv":0.9,"mark_price":0.1,"synthetic_future":{"instrument_name":"BTC-28JUN19","mark_price":10000.0}},
+            {"instrument_name":"BTC-28JUN19-10000-C","iv":0.8,"mark_price":0.05,"synthetic_future":{"instrument_name":"BTC-28JUN19","mark_price":10000.0}},
+            {"instrument_name":"BTC-28JUN19-12000-C","iv":0.85,"mark_price":0.02,"synthetic_future":{"instrument_name":"BTC-28JUN19","mark_price":10000.0}},
+            {"instrument_name":"BTC-27SEP19-10000-C","iv":0.7,"mark_price":0.1,"synthetic_future":{"instrument_name":"BTC-27SEP19","mark_price":10200.0}},
+            {"instrument_name":"BTC-27SEP19-10000-P","iv":0.7,"mark_price":0.1,"synthetic_future":null}
+        ]"#,
+    )
+    .unwrap();
+    let mut surface = VolSurface::new("BTC");
+    surface.update_mark_prices(&data);
+    surface
+}
+
+#[test]
+fn interpolate_strikes() {
+    let surface = surface();
+    let now = Utc.with_ymd_and_hms(2019, 5, 1, 8, 0, 0).unwrap();
+    let expiry = Utc.with_ymd_and_hms(2019, 6, 28, 8, 0, 0).unwrap();
+
+    assert_eq!(surface.expiries().count(), 2);
+    assert_close(
+        surface.implied_vol(10000., expiry, now).unwrap(),
+        0.8,
+        1e-12,
+    );
+    assert_close(surface.implied_vol(5000., expiry, now).unwrap(), 0.9, 1e-12);
+    let k = (9000f64 / 10000.).ln();
+    let (k1, k2) = ((8000f64 / 10000.).ln(), 0.);
+    assert_close(
+        surface.implied_vol(9000., expiry, now).unwrap(),
+        0.9 + (0.8 - 0.9) * (k - k1) / (k2 - k1),
+        1e-12,
+    );
+}
+
+#[test]
+fn interpolate_expiries() {
+    let mut surface = surface();
+    let now = Utc.with_ymd_and_hms(2019, 5, 1, 8, 0, 0).unwrap();
+    let expiry = Utc.with_ymd_and_hms(2019, 8, 1, 8, 0, 0).unwrap();
+    let (t1, t2, t) = (58. / 365., 149. / 365., 92. / 365.);
+
+    let forward = surface.forward(expiry, now).unwrap();
+    assert_close(forward, 10000. + 200. * (t - t1) / (t2 - t1), 1e-9);
+
+    let vol = surface.implied_vol_at_moneyness(0., expiry, now).unwrap();
+    let variance = 0.64 * t1 + (0.49 * t2 - 0.64 * t1) * (t - t1) / (t2 - t1);
+    assert_close(vol, (variance / t).sqrt(), 1e-12);
+
+    surface.remove_expired(Utc.with_ymd_and_hms(2019, 7, 1, 0, 0, 0).unwrap());
+    assert_eq!(surface.expiries().count(), 1);
+}
+
+#[test]
+fn rejects_other_underlyings_and_prefers_otm() {
+    let mut surface = VolSurface::new("BTC");
+    let now = Utc.with_ymd_and_hms(2019, 5, 1, 8, 0, 0).unwrap();
+    let expiry = Utc.with_ymd_and_hms(2019, 6, 28, 8, 0, 0).unwrap();
+    let option = |name: &str| name.parse::<InstrumentName>().unwrap();
+
+    assert!(!surface.insert(&option("ETH-28JUN19-250-C"), 1.2, Some(250.)));
+    assert!(!surface.insert(&option("BTC-28JUN19"), 0.8, Some(10000.)));
+    assert_eq!(surface.expiries().count(), 0);
+
+    // Below the forward the put wins, whatever the order of the updates
+    assert!(surface.insert(&option("BTC-28JUN19-9000-P"), 0.85, Some(10000.)));
+    assert!(surface.insert(&option("BTC-28JUN19-9000-C"), 0.95, None));
+    assert_close(
+        surface.implied_vol(9000., expiry, now).unwrap(),
+        0.85,
+        1e-12,
+    );
+
+    // At and above it the call does
+    assert!(surface.insert(&option("BTC-28JUN19-11000-P"), 0.95, None));
+    assert!(surface.insert(&option("BTC-28JUN19-11000-C"), 0.75, None));
+    assert!(surface.insert(&option("BTC-28JUN19-11000-P"), 0.95, None));
+    assert_close(
+        surface.implied_vol(11000., expiry, now).unwrap(),
+        0.75,
+        1e-12,
+    );
+}
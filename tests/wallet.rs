@@ -1,8 +1,14 @@
-use deribit::models::{AuthRequest, Currency, GetTransfersRequest};
+use deribit::models::{
+    AddressBookType, AuthRequest, Currency, GetAddressBookRequest, GetCurrentDepositAddressRequest,
+    GetDepositsRequest, GetTransfersRequest, GetTransfersResponse, GetWithdrawalsRequest,
+    PaginatedRequest, Priority, WithdrawRequest, WithdrawResponse, WithdrawState,
+};
 use deribit::DeribitBuilder;
 use dotenv::dotenv;
 use failure::Error;
 use fehler::throws;
+use futures::TryStreamExt;
+use serde_json::{from_value, json, to_value};
 use std::env::var;
 use tokio::runtime::Runtime;
 
@@ -22,7 +28,113 @@ fn get_transfers() {
         let _ = client.call(req).await?.await?;
 
         let req = GetTransfersRequest::with_currency(Currency::BTC);
-        Ok::<_, Error>(client.call(req).await?.await?)
+        client.call(req).await?.await
     };
     let _ = rt.block_on(fut)?;
 }
+
+#[test]
+#[throws(Error)]
+fn deposits_and_withdrawals() {
+    let _ = dotenv();
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = GetDepositsRequest {
+            count: Some(10),
+            ..GetDepositsRequest::with_currency(Currency::BTC)
+        };
+        let _ = client.call_paginated(req).try_collect::<Vec<_>>().await?;
+
+        let req = GetWithdrawalsRequest {
+            count: Some(10),
+            ..GetWithdrawalsRequest::with_currency(Currency::BTC)
+        };
+        let _ = client.call_paginated(req).try_collect::<Vec<_>>().await?;
+
+        let req = GetCurrentDepositAddressRequest::new(Currency::BTC);
+        let _ = client.call(req).await?.await?;
+
+        let req = GetAddressBookRequest::new(Currency::BTC, AddressBookType::Withdrawal);
+        let _ = client.call(req).await?.await?;
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn withdraw_models() {
+    let req = WithdrawRequest::with_priority(
+        Currency::BTC,
+        "2N8udZGBc1hLRCFsU9kGwB5",
+        0.5,
+        Priority::High,
+    );
+    assert_eq!(
+        to_value(req)?,
+        json!({
+            "currency": "BTC",
+            "address": "2N8udZGBc1hLRCFsU9kGwB5",
+            "amount": 0.5,
+            "priority": "high"
+        })
+    );
+
+    let withdrawal: WithdrawResponse = from_value(json!({
+        "address": "2N8udZGBc1hLRCFsU9kGwB5",
+        "amount": 0.5,
+        "confirmed_timestamp": 1550571111000u64,
+        "created_timestamp": 1550571000000u64,
+        "currency": "BTC",
+        "fee": 0.0001,
+        "id": 1,
+        "priority": 0.15,
+        "state": "confirmed",
+        "transaction_id": null,
+        "updated_timestamp": 1550571111000u64
+    }))?;
+    assert_eq!(withdrawal.state, WithdrawState::Confirmed);
+}
+
+#[test]
+#[throws(Error)]
+fn transfers_paging() {
+    let transfer = |id: i64| {
+        json!({
+            "amount": 0.2,
+            "created_timestamp": 1550579457727u64,
+            "currency": "BTC",
+            "direction": "payment",
+            "id": id,
+            "other_side": "2MzyQc5Tkik61kJbEpJV5D5H9VfWHZK9Sgy",
+            "state": "prepared",
+            "type": "user",
+            "updated_timestamp": 1550579457727u64
+        })
+    };
+
+    let req = GetTransfersRequest {
+        count: Some(2),
+        ..GetTransfersRequest::with_currency(Currency::BTC)
+    };
+    let page: GetTransfersResponse =
+        from_value(json!({"count": 3, "data": [transfer(1), transfer(2)]}))?;
+    let (items, next) = req.paginate(page)?;
+    assert_eq!(items.len(), 2);
+    let next = next.unwrap();
+    assert_eq!(next.offset, Some(2));
+
+    let page: GetTransfersResponse = from_value(json!({"count": 3, "data": [transfer(3)]}))?;
+    let (items, next) = next.paginate(page)?;
+    assert_eq!(items.len(), 1);
+    assert!(next.is_none());
+}
@@ -0,0 +1,142 @@
+use chrono::{Duration, TimeZone, Utc};
+use deribit::models::Withdrawal;
+use deribit::models::{Currency, SubmitTransferToSubaccountRequest, WithdrawRequest};
+use deribit::withdrawal_guard::{AuditOutcome, Receipt, WithdrawalGuard, WithdrawalRejection};
+use deribit::DeribitError;
+use failure::err_msg;
+use serde_json::json;
+
+const ADDRESS: &str = "2N8udZGBc1hLRCFsU9kGwB5";
+
+#[test]
+fn guard_policy() {
+    let now = Utc.with_ymd_and_hms(2020, 3, 1, 12, 0, 0).unwrap();
+    let mut guard = WithdrawalGuard::new();
+
+    let withdraw = WithdrawRequest::new(Currency::BTC, ADDRESS, 0.5);
+    assert_eq!(
+        guard.prepare(withdraw.clone(), now).unwrap_err(),
+        WithdrawalRejection::DestinationNotAllowed {
+            currency: Currency::BTC,
+            destination: ADDRESS.to_string()
+        }
+    );
+
+    guard.allow(Currency::BTC, ADDRESS);
+    assert_eq!(
+        guard.prepare(withdraw.clone(), now).unwrap_err(),
+        WithdrawalRejection::NoDailyCap(Currency::BTC)
+    );
+
+    guard.set_daily_cap(Currency::BTC, 1.);
+    let pending = guard.prepare(withdraw, now).unwrap();
+    assert_eq!(pending.movement().amount(), 0.5);
+
+    let too_much = WithdrawRequest::new(Currency::BTC, ADDRESS, 1.5);
+    assert!(matches!(
+        guard.prepare(too_much, now).unwrap_err(),
+        WithdrawalRejection::DailyCapExceeded { cap, .. } if cap == 1.
+    ));
+
+    // Subaccounts are allowlisted by id
+    let transfer = SubmitTransferToSubaccountRequest::new(Currency::BTC, 0.1, 7);
+    guard.prepare(transfer.clone(), now).unwrap_err();
+    guard.allow(Currency::BTC, "7");
+    guard.prepare(transfer, now + Duration::days(1)).unwrap();
+
+    let outcomes: Vec<_> = guard
+        .audit_log()
+        .iter()
+        .map(|entry| matches!(entry.outcome, AuditOutcome::DryRun))
+        .collect();
+    assert_eq!(outcomes, [false, false, true, false, false, true]);
+}
+
+#[test]
+fn guard_accounting() {
+    let now = Utc.with_ymd_and_hms(2020, 3, 1, 12, 0, 0).unwrap();
+    let today = now.date_naive();
+    let mut guard = WithdrawalGuard::new();
+    guard.allow(Currency::BTC, ADDRESS);
+    guard.allow(Currency::BTC, "7");
+    guard.set_daily_cap(Currency::BTC, 1.);
+
+    let withdrawal: Withdrawal = serde_json::from_value(json!({
+        "address": ADDRESS,
+        "amount": 0.5,
+        "confirmed_timestamp": null,
+        "created_timestamp": 1583064000000u64,
+        "currency": "BTC",
+        "fee": 0.0001,
+        "id": 21,
+        "priority": 1.,
+        "state": "unconfirmed",
+        "transaction_id": null,
+        "updated_timestamp": 1583064000000u64
+    }))
+    .unwrap();
+
+    // Sent: the amount stays spent
+    let pending = guard
+        .prepare(WithdrawRequest::new(Currency::BTC, ADDRESS, 0.5), now)
+        .unwrap();
+    let movement = guard.begin(pending, now).unwrap();
+    assert_eq!(guard.spent_on(&Currency::BTC, today), 0.5);
+    guard
+        .settle(movement, now, Ok(Receipt::Withdrawal(withdrawal)))
+        .unwrap();
+    assert_eq!(guard.spent_on(&Currency::BTC, today), 0.5);
+
+    // Refused by Deribit: the reservation is released
+    let transfer = SubmitTransferToSubaccountRequest::new(Currency::BTC, 0.3, 7);
+    let pending = guard.prepare(transfer.clone(), now).unwrap();
+    let movement = guard.begin(pending, now).unwrap();
+    assert_eq!(guard.spent_on(&Currency::BTC, today), 0.8);
+    let refused = DeribitError::RemoteError {
+        code: 10000,
+        message: "not enough funds".to_string(),
+    };
+    assert!(guard.settle(movement, now, Err(refused.into())).is_err());
+    assert_eq!(guard.spent_on(&Currency::BTC, today), 0.5);
+
+    // No answer: the amount may have gone out and keeps counting
+    let pending = guard.prepare(transfer, now).unwrap();
+    let movement = guard.begin(pending, now).unwrap();
+    assert!(guard
+        .settle(movement, now, Err(err_msg("timed out")))
+        .is_err());
+    assert_eq!(guard.spent_on(&Currency::BTC, today), 0.8);
+
+    // Checked again on execution: the spending changed since the dry run
+    let small = SubmitTransferToSubaccountRequest::new(Currency::BTC, 0.1, 7);
+    let pending = guard.prepare(small, now).unwrap();
+    let other = guard
+        .prepare(WithdrawRequest::new(Currency::BTC, ADDRESS, 0.2), now)
+        .unwrap();
+    guard.begin(other, now).unwrap();
+    assert!(matches!(
+        guard.begin(pending, now).unwrap_err(),
+        WithdrawalRejection::DailyCapExceeded { spent, .. } if spent == 1.
+    ));
+
+    // Released by hand once a movement turned out not to have gone out, never below 0
+    guard.release(&Currency::BTC, today, 0.3);
+    assert!((guard.spent_on(&Currency::BTC, today) - 0.7).abs() < 1e-9);
+    guard.release(&Currency::BTC, today, 5.);
+    assert_eq!(guard.spent_on(&Currency::BTC, today), 0.);
+    guard.release(&Currency::ETH, today, 1.);
+    assert_eq!(guard.spent_on(&Currency::ETH, today), 0.);
+
+    let outcomes: Vec<_> = guard
+        .audit_log()
+        .iter()
+        .filter_map(|entry| match &entry.outcome {
+            AuditOutcome::DryRun => None,
+            AuditOutcome::Rejected(_) => Some("rejected".to_string()),
+            AuditOutcome::Sent { id } => Some(format!("sent {}", id)),
+            AuditOutcome::Failed(_) => Some("failed".to_string()),
+            AuditOutcome::Unknown(_) => Some("unknown".to_string()),
+        })
+        .collect();
+    assert_eq!(outcomes, ["sent 21", "failed", "unknown", "rejected"]);
+}